@@ -0,0 +1,35 @@
+//! Dumps a disassembly of a ROM's PRG-ROM, starting from the reset vector.
+//!
+//! Usage: `cargo run --example disasm -- path/to/game.nes`
+//!
+//! Thin CLI wrapper around [`nes_emulator::disasm::disassemble`]; see that
+//! function for the actual decode logic.
+
+use nes_emulator::bus::Bus;
+use nes_emulator::cpu::CPU;
+use nes_emulator::disasm::disassemble;
+use nes_emulator::rom::Rom;
+
+fn main() {
+    let path = std::env::args().nth(1).unwrap_or_else(|| {
+        eprintln!("usage: disasm <path/to/game.nes>");
+        std::process::exit(1);
+    });
+
+    let raw = std::fs::read(&path).unwrap_or_else(|err| {
+        eprintln!("failed to read {path}: {err}");
+        std::process::exit(1);
+    });
+    let rom: Rom = raw.try_into().unwrap_or_else(|err| {
+        eprintln!("failed to parse {path}: {err}");
+        std::process::exit(1);
+    });
+
+    let mut cpu = CPU::new(Bus::new(rom));
+    cpu.power_on();
+    let start = cpu.program_counter;
+
+    for line in disassemble(&mut cpu, start, 0xffff) {
+        println!("{line}");
+    }
+}
@@ -0,0 +1,402 @@
+//! The NES Audio Processing Unit.
+//!
+//! Built up incrementally; this revision contains the length-counter logic
+//! shared by the pulse, triangle and noise channels, a WAV export helper
+//! for inspecting drained samples without an audio backend, and enough of
+//! the pulse channels' register state ([`Apu`]) to make writes to them
+//! observable via [`Apu::debug_state`]. The actual timer/duty-sequencer
+//! audio generation, and the triangle/noise/DMC channels, land in a later
+//! revision.
+
+use std::fs::File;
+use std::io::{self, Write};
+
+/// Maps the top 5 bits of a channel's fourth register write to the number
+/// of frame-counter clocks its length counter should count down from.
+pub const LENGTH_TABLE: [u8; 32] = [
+    10, 254, 20, 2, 40, 4, 80, 6, 160, 8, 60, 10, 14, 12, 26, 14, 12, 16, 24, 18, 48, 20, 96, 22,
+    192, 24, 72, 26, 16, 28, 32, 30,
+];
+
+/// Shared length-counter logic used by the pulse, triangle and noise
+/// channels to silence themselves after a set number of frame-counter
+/// clocks.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LengthCounter {
+    value: u8,
+    halt: bool,
+    enabled: bool,
+}
+
+impl LengthCounter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enables or disables the channel. Disabling immediately silences it;
+    /// it stays silent until re-enabled and reloaded.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.value = 0;
+        }
+    }
+
+    pub fn set_halt(&mut self, halt: bool) {
+        self.halt = halt;
+    }
+
+    /// Reloads the counter from `LENGTH_TABLE[index]`, but only if the
+    /// channel is currently enabled.
+    pub fn load(&mut self, index: u8) {
+        if self.enabled {
+            self.value = LENGTH_TABLE[index as usize];
+        }
+    }
+
+    /// Clocks the counter once, as driven by the frame counter. No-op
+    /// while halted or already silent.
+    pub fn clock(&mut self) {
+        if !self.halt && self.value > 0 {
+            self.value -= 1;
+        }
+    }
+
+    /// True while the channel should still be producing sound.
+    pub fn is_active(&self) -> bool {
+        self.value > 0
+    }
+
+    /// The raw countdown value, for debug readback (e.g. [`Apu::debug_state`]).
+    pub fn value(&self) -> u8 {
+        self.value
+    }
+
+    /// Whether the channel is currently enabled (see [`Self::set_enabled`]).
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Identifies one of the APU's five audio channels, for per-channel
+/// mute/solo control.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApuChannel {
+    Pulse1,
+    Pulse2,
+    Triangle,
+    Noise,
+    Dmc,
+}
+
+/// Per-channel mute flags for debugging sound issues.
+///
+/// The pulse/triangle/noise/DMC channel generators and the final mixer
+/// land in a later revision; this is the mute-tracking half of that work,
+/// built and tested ahead of it. Once a channel lands, its output sample
+/// passes through [`Self::gate`] before reaching the mixer: a muted
+/// channel keeps clocking its length counter, envelope, and timer exactly
+/// as before, it just contributes silence to the final mix.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ChannelMutes {
+    pulse1: bool,
+    pulse2: bool,
+    triangle: bool,
+    noise: bool,
+    dmc: bool,
+}
+
+impl ChannelMutes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_channel_muted(&mut self, channel: ApuChannel, muted: bool) {
+        *self.flag_mut(channel) = muted;
+    }
+
+    pub fn is_channel_muted(&self, channel: ApuChannel) -> bool {
+        *self.flag(channel)
+    }
+
+    /// Returns `sample` unchanged, or silence if `channel` is muted.
+    pub fn gate(&self, channel: ApuChannel, sample: f32) -> f32 {
+        if self.is_channel_muted(channel) {
+            0.0
+        } else {
+            sample
+        }
+    }
+
+    fn flag(&self, channel: ApuChannel) -> &bool {
+        match channel {
+            ApuChannel::Pulse1 => &self.pulse1,
+            ApuChannel::Pulse2 => &self.pulse2,
+            ApuChannel::Triangle => &self.triangle,
+            ApuChannel::Noise => &self.noise,
+            ApuChannel::Dmc => &self.dmc,
+        }
+    }
+
+    fn flag_mut(&mut self, channel: ApuChannel) -> &mut bool {
+        match channel {
+            ApuChannel::Pulse1 => &mut self.pulse1,
+            ApuChannel::Pulse2 => &mut self.pulse2,
+            ApuChannel::Triangle => &mut self.triangle,
+            ApuChannel::Noise => &mut self.noise,
+            ApuChannel::Dmc => &mut self.dmc,
+        }
+    }
+}
+
+/// A pulse channel's register state: period, envelope/volume and length
+/// counter. The duty-cycle sequencer and the envelope's decay/sweep units
+/// aren't modeled yet — only enough of `$4000`-`$4003`/`$4004`-`$4007` to
+/// make writes to them observable, primarily via [`Apu::debug_state`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PulseChannel {
+    length: LengthCounter,
+    period: u16,
+    envelope_volume: u8,
+}
+
+impl PulseChannel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `$4000`/`$4004`: the length-counter-halt flag (bit 5) and constant
+    /// volume / envelope period (bits 0-3). Duty cycle (bits 6-7) isn't
+    /// modeled yet.
+    pub fn write_control(&mut self, value: u8) {
+        self.length.set_halt(value & 0b0010_0000 != 0);
+        self.envelope_volume = value & 0b0000_1111;
+    }
+
+    /// `$4002`/`$4006`: the period's low 8 bits.
+    pub fn write_period_low(&mut self, value: u8) {
+        self.period = (self.period & 0xff00) | value as u16;
+    }
+
+    /// `$4003`/`$4007`: the period's high 3 bits (bits 0-2) and the length
+    /// counter's reload index (bits 3-7).
+    pub fn write_period_high_and_length(&mut self, value: u8) {
+        self.period = (self.period & 0x00ff) | ((value as u16 & 0x07) << 8);
+        self.length.load(value >> 3);
+    }
+
+    /// Driven by a `$4015` write: enabling/disabling the channel silences
+    /// or re-arms its length counter (see [`LengthCounter::set_enabled`]).
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.length.set_enabled(enabled);
+    }
+
+    fn debug_state(&self) -> PulseChannelDebugState {
+        PulseChannelDebugState {
+            period: self.period,
+            length_counter: self.length.value(),
+            envelope_volume: self.envelope_volume,
+            enabled: self.length.is_enabled(),
+        }
+    }
+}
+
+/// Debug readback of one pulse channel's register state. See
+/// [`Apu::debug_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PulseChannelDebugState {
+    pub period: u16,
+    pub length_counter: u8,
+    pub envelope_volume: u8,
+    pub enabled: bool,
+}
+
+/// Debug-only readback of the APU's channel state, for unit tests that
+/// need to verify a register write took effect without inferring it from
+/// audio output — most APU registers are write-only on real hardware.
+/// Covers the pulse channels for now; triangle/noise/DMC follow once
+/// their channel types land.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ApuDebugState {
+    pub pulse1: PulseChannelDebugState,
+    pub pulse2: PulseChannelDebugState,
+}
+
+/// The APU's register-facing state. Owns the two pulse channels today;
+/// triangle/noise/DMC join once their channel types land (see the
+/// module docs).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Apu {
+    pulse1: PulseChannel,
+    pulse2: PulseChannel,
+}
+
+impl Apu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Dispatches a CPU write to the APU's `$4000`-`$4015` register range.
+    /// Addresses this revision doesn't yet model are silently ignored, the
+    /// same way an unimplemented write would be if routed through here
+    /// from the bus.
+    pub fn write_register(&mut self, addr: u16, value: u8) {
+        match addr {
+            0x4000 => self.pulse1.write_control(value),
+            0x4002 => self.pulse1.write_period_low(value),
+            0x4003 => self.pulse1.write_period_high_and_length(value),
+            0x4004 => self.pulse2.write_control(value),
+            0x4006 => self.pulse2.write_period_low(value),
+            0x4007 => self.pulse2.write_period_high_and_length(value),
+            0x4015 => {
+                self.pulse1.set_enabled(value & 0b0000_0001 != 0);
+                self.pulse2.set_enabled(value & 0b0000_0010 != 0);
+            }
+            _ => {}
+        }
+    }
+
+    /// Snapshot of both pulse channels' current period, length counter,
+    /// envelope volume and enabled flag. See [`ApuDebugState`].
+    pub fn debug_state(&self) -> ApuDebugState {
+        ApuDebugState {
+            pulse1: self.pulse1.debug_state(),
+            pulse2: self.pulse2.debug_state(),
+        }
+    }
+}
+
+/// Writes `samples` (mono, `-1.0..=1.0`) to `path` as a 16-bit PCM WAV file,
+/// for inspecting drained APU audio in an editor without an audio backend.
+/// Hand-writes the 44-byte canonical header rather than pulling in a crate.
+pub fn export_wav(samples: &[f32], sample_rate: u32, path: &str) -> io::Result<()> {
+    const BITS_PER_SAMPLE: u16 = 16;
+    const CHANNELS: u16 = 1;
+
+    let block_align = CHANNELS * (BITS_PER_SAMPLE / 8);
+    let byte_rate = sample_rate * block_align as u32;
+    let data_size = samples.len() as u32 * block_align as u32;
+
+    let mut file = File::create(path)?;
+
+    file.write_all(b"RIFF")?;
+    file.write_all(&(36 + data_size).to_le_bytes())?;
+    file.write_all(b"WAVE")?;
+
+    file.write_all(b"fmt ")?;
+    file.write_all(&16u32.to_le_bytes())?; // fmt chunk size
+    file.write_all(&1u16.to_le_bytes())?; // PCM
+    file.write_all(&CHANNELS.to_le_bytes())?;
+    file.write_all(&sample_rate.to_le_bytes())?;
+    file.write_all(&byte_rate.to_le_bytes())?;
+    file.write_all(&block_align.to_le_bytes())?;
+    file.write_all(&BITS_PER_SAMPLE.to_le_bytes())?;
+
+    file.write_all(b"data")?;
+    file.write_all(&data_size.to_le_bytes())?;
+    for &sample in samples {
+        let clamped = sample.clamp(-1.0, 1.0);
+        let pcm = (clamped * i16::MAX as f32) as i16;
+        file.write_all(&pcm.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_length_counter_silences_after_expiring() {
+        let mut lc = LengthCounter::new();
+        lc.set_enabled(true);
+        lc.load(0); // LENGTH_TABLE[0] == 10
+        assert!(lc.is_active());
+        for _ in 0..10 {
+            lc.clock();
+        }
+        assert!(!lc.is_active());
+    }
+
+    #[test]
+    fn test_length_counter_does_not_reload_when_disabled() {
+        let mut lc = LengthCounter::new();
+        lc.load(0); // channel not enabled - load is ignored
+        assert!(!lc.is_active());
+    }
+
+    #[test]
+    fn test_length_counter_halt_freezes_value() {
+        let mut lc = LengthCounter::new();
+        lc.set_enabled(true);
+        lc.load(3); // LENGTH_TABLE[3] == 2
+        lc.set_halt(true);
+        lc.clock();
+        lc.clock();
+        lc.clock();
+        assert!(lc.is_active());
+    }
+
+    #[test]
+    fn test_muting_pulse1_zeroes_its_contribution_and_unmuting_restores_it() {
+        let mut mutes = ChannelMutes::new();
+        let sample = 0.75;
+
+        assert_eq!(mutes.gate(ApuChannel::Pulse1, sample), sample);
+
+        mutes.set_channel_muted(ApuChannel::Pulse1, true);
+        assert_eq!(mutes.gate(ApuChannel::Pulse1, sample), 0.0);
+        assert_eq!(mutes.gate(ApuChannel::Pulse2, sample), sample); // other channels unaffected
+
+        mutes.set_channel_muted(ApuChannel::Pulse1, false);
+        assert_eq!(mutes.gate(ApuChannel::Pulse1, sample), sample);
+    }
+
+    #[test]
+    fn test_apu_debug_state_reflects_a_pulse_periods_and_length_writes() {
+        let mut apu = Apu::new();
+        apu.write_register(0x4015, 0b0000_0001); // enable pulse1 only
+        apu.write_register(0x4000, 0b0001_0101); // halt (bit 5) clear, envelope volume 5
+        apu.write_register(0x4002, 0xab); // period low byte
+        apu.write_register(0x4003, 0b0000_1011); // length index 1, period high bits 011
+
+        let state = apu.debug_state();
+
+        assert_eq!(state.pulse1.period, 0x3ab);
+        assert_eq!(state.pulse1.length_counter, LENGTH_TABLE[1]);
+        assert_eq!(state.pulse1.envelope_volume, 5);
+        assert!(state.pulse1.enabled);
+
+        // pulse2 was never written, and was never enabled via $4015.
+        assert_eq!(state.pulse2, PulseChannelDebugState::default());
+    }
+
+    #[test]
+    fn test_export_wav_round_trips_header_and_sample_count() {
+        let sample_rate = 44_100u32;
+        let samples: Vec<f32> = (0..100).map(|i| (i as f32 * 0.1).sin()).collect();
+
+        let mut path = std::env::temp_dir();
+        path.push("nes_emulator_synth115_test.wav");
+        export_wav(&samples, sample_rate, path.to_str().unwrap()).unwrap();
+
+        let raw = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(&raw[0..4], b"RIFF");
+        assert_eq!(&raw[8..12], b"WAVE");
+        assert_eq!(&raw[12..16], b"fmt ");
+        let channels = u16::from_le_bytes([raw[22], raw[23]]);
+        let rate = u32::from_le_bytes([raw[24], raw[25], raw[26], raw[27]]);
+        let bits_per_sample = u16::from_le_bytes([raw[34], raw[35]]);
+        assert_eq!(channels, 1);
+        assert_eq!(rate, sample_rate);
+        assert_eq!(bits_per_sample, 16);
+
+        assert_eq!(&raw[36..40], b"data");
+        let data_size = u32::from_le_bytes([raw[40], raw[41], raw[42], raw[43]]);
+        assert_eq!(data_size as usize, samples.len() * 2);
+        assert_eq!(raw.len(), 44 + samples.len() * 2);
+    }
+}
@@ -0,0 +1,112 @@
+//! A tiny convenience assembler for quick experiments and readable tests.
+//!
+//! [`Instr`] values encode straight to machine code via [`assemble`], and
+//! [`run_asm`] assembles, loads, and runs a program in one call. This covers
+//! only the handful of mnemonics/addressing modes that quick experiments and
+//! tests actually reach for; extend [`Instr`]/[`Operand`] as more are needed.
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+use crate::cpu::CPU;
+use crate::mem::{FlatMemory, Mem};
+
+/// An addressing-mode operand for instructions that take one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Imm(u8),
+    ZeroPage(u8),
+    Absolute(u16),
+}
+
+/// A small but growing subset of 6502 mnemonics, enough for quick
+/// REPL-style experiments and tests. Extend as new tests need more coverage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Instr {
+    Lda(Operand),
+    Ldx(Operand),
+    Ldy(Operand),
+    Tax,
+    Tay,
+    Inx,
+    Iny,
+    Brk,
+}
+
+/// Encodes `program` to its machine code bytes, in order.
+pub fn assemble(program: &[Instr]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for instr in program {
+        encode_into(&mut bytes, *instr);
+    }
+    bytes
+}
+
+fn encode_into(bytes: &mut Vec<u8>, instr: Instr) {
+    match instr {
+        Instr::Lda(Operand::Imm(v)) => bytes.extend_from_slice(&[0xa9, v]),
+        Instr::Lda(Operand::ZeroPage(a)) => bytes.extend_from_slice(&[0xa5, a]),
+        Instr::Lda(Operand::Absolute(a)) => {
+            bytes.push(0xad);
+            bytes.extend_from_slice(&a.to_le_bytes());
+        }
+        Instr::Ldx(Operand::Imm(v)) => bytes.extend_from_slice(&[0xa2, v]),
+        Instr::Ldx(Operand::ZeroPage(a)) => bytes.extend_from_slice(&[0xa6, a]),
+        Instr::Ldx(Operand::Absolute(a)) => {
+            bytes.push(0xae);
+            bytes.extend_from_slice(&a.to_le_bytes());
+        }
+        Instr::Ldy(Operand::Imm(v)) => bytes.extend_from_slice(&[0xa0, v]),
+        Instr::Ldy(Operand::ZeroPage(a)) => bytes.extend_from_slice(&[0xa4, a]),
+        Instr::Ldy(Operand::Absolute(a)) => {
+            bytes.push(0xac);
+            bytes.extend_from_slice(&a.to_le_bytes());
+        }
+        Instr::Tax => bytes.push(0xaa),
+        Instr::Tay => bytes.push(0xa8),
+        Instr::Inx => bytes.push(0xe8),
+        Instr::Iny => bytes.push(0xc8),
+        Instr::Brk => bytes.push(0x00),
+    }
+}
+
+/// Assembles `program`, loads it at `0x8000`, points the reset vector at it,
+/// runs to the trailing `Brk`, and returns the CPU for inspection. Meant for
+/// quick experiments and tests that read like:
+/// `let cpu = run_asm(&[Lda(Imm(5)), Tax, Brk]); assert_eq!(cpu.register_x, 5);`
+pub fn run_asm(program: &[Instr]) -> CPU<FlatMemory> {
+    let bytes = assemble(program);
+    let mut cpu = CPU::new(FlatMemory::new());
+    for (i, byte) in bytes.iter().enumerate() {
+        cpu.mem_write(0x8000 + i as u16, *byte);
+    }
+    cpu.mem_write_u16(0xFFFC, 0x8000);
+    cpu.power_on();
+    cpu.run();
+    cpu
+}
+
+#[cfg(test)]
+mod test {
+    use super::Instr::*;
+    use super::Operand::*;
+    use super::*;
+
+    #[test]
+    fn test_run_asm_loads_a_transfers_to_x_and_halts_on_brk() {
+        let cpu = run_asm(&[Lda(Imm(5)), Tax, Brk]);
+        assert_eq!(cpu.register_x, 5);
+    }
+
+    #[test]
+    fn test_run_asm_supports_ldx_and_increments() {
+        let cpu = run_asm(&[Ldx(Imm(7)), Inx, Inx, Brk]);
+        assert_eq!(cpu.register_x, 9);
+    }
+
+    #[test]
+    fn test_assemble_encodes_immediate_and_absolute_operands() {
+        let bytes = assemble(&[Lda(Imm(5)), Ldy(Absolute(0x1234)), Brk]);
+        assert_eq!(bytes, vec![0xa9, 0x05, 0xac, 0x34, 0x12, 0x00]);
+    }
+}
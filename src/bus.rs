@@ -0,0 +1,649 @@
+use std::ops::RangeInclusive;
+
+use crate::joypad::Joypad;
+use crate::mapper::Mapper;
+use crate::mem::Mem;
+use crate::ppu::NesPpu;
+use crate::rom::Rom;
+
+const RAM: u16 = 0x0000;
+const RAM_MIRRORS_END: u16 = 0x1fff;
+const PPU_REGISTERS_MIRRORS_END: u16 = 0x3fff;
+const PRG_ROM_START: u16 = 0x8000;
+const PRG_ROM_BANK_SIZE: u16 = 0x4000;
+
+/// Resolves a CPU write that lands in ROM space under bus-conflict
+/// emulation. Bank-switching mappers that latch their bank-select register
+/// straight off a write to ROM space (UxROM, CNROM, and others) have both
+/// the CPU and the ROM chip driving the bus at once; the NES's open-drain
+/// bus ANDs the two together, so the byte that actually reaches the latch
+/// is `written & rom_byte`, not `written` outright. Games route these
+/// writes through a byte that matches the ROM contents (or through RAM)
+/// specifically to avoid depending on this.
+///
+/// NROM — the only mapper this emulator wires up today — has no writable
+/// registers, so the conflict never arises for it; `has_bus_conflicts`
+/// comes from [`Mapper::has_bus_conflicts`], consulted by
+/// [`Bus::mem_write`] for whatever mapper is registered via
+/// [`Bus::set_mapper`].
+pub fn resolve_bus_conflict(written: u8, rom_byte: u8, has_bus_conflicts: bool) -> u8 {
+    if has_bus_conflicts {
+        written & rom_byte
+    } else {
+        written
+    }
+}
+
+/// A custom memory-mapped peripheral pluggable onto the bus via
+/// [`Bus::map_device`], for experimenting with homebrew hardware without
+/// touching the core address decode.
+pub trait BusDevice {
+    fn read(&mut self, addr: u16) -> u8;
+    fn write(&mut self, addr: u16, data: u8);
+}
+
+/// The NES system bus: CPU-visible address space, fanning out to internal
+/// RAM, the PPU's memory-mapped registers, a controller port, and the
+/// cartridge's PRG ROM.
+pub struct Bus {
+    cpu_vram: [u8; 2048],
+    prg_rom: Vec<u8>,
+    ppu: NesPpu,
+    joypad1: Joypad,
+    /// When set, writes into PRG ROM space (almost always a program bug on
+    /// an NROM cart with no mapper registers) are recorded instead of
+    /// silently dropped. Off by default so normal runs pay nothing for it.
+    pub strict_rom: bool,
+    rom_write_violations: Vec<u16>,
+    /// Custom devices registered via [`Self::map_device`], checked before
+    /// the normal address decode. No core hardware uses this; it's an
+    /// extension point for homebrew peripherals.
+    devices: Vec<(RangeInclusive<u16>, Box<dyn BusDevice>)>,
+    /// A bank-switching mapper's IRQ source, registered via
+    /// [`Self::set_mapper`]. `None` for NROM (the only mapper this emulator
+    /// runs today), which has no mapper registers at all.
+    mapper: Option<Box<dyn Mapper>>,
+    /// The running dot-accurate clock, advanced by [`Self::tick`]. See
+    /// [`Self::master_clock`].
+    clock_count: u64,
+}
+
+impl Bus {
+    pub fn new(rom: Rom) -> Self {
+        Self::new_with_ram_fill(rom, 0)
+    }
+
+    /// Like [`Self::new`], but powers the 2KB of internal RAM on filled
+    /// with `fill` instead of zero. Real hardware powers on with semi-random
+    /// RAM; this gives tests a deterministic, chosen starting state instead.
+    pub fn new_with_ram_fill(rom: Rom, fill: u8) -> Self {
+        Bus {
+            cpu_vram: [fill; 2048],
+            prg_rom: rom.prg_rom,
+            ppu: NesPpu::new_with_chr_ram(rom.chr_rom, rom.mirroring, rom.chr_ram),
+            joypad1: Joypad::new(),
+            strict_rom: false,
+            rom_write_violations: Vec::new(),
+            devices: Vec::new(),
+            mapper: None,
+            clock_count: 0,
+        }
+    }
+
+    /// Advances the bus's shared dot-accurate clock by `cpu_cycles` CPU
+    /// cycles and ticks the PPU to match (three dots per CPU cycle, see
+    /// [`NesPpu::tick`]). Call this once per CPU step with exactly the
+    /// cycles it just spent, so [`Self::master_clock`] never drifts from
+    /// the PPU's own raster position — a debugger can then compare the two
+    /// to catch either one silently falling behind.
+    ///
+    /// Nothing here ticks an APU — it isn't wired onto the bus at all yet
+    /// (see [`crate::apu`]) — so there's nothing there to keep in sync
+    /// today; this will cover it too once that lands.
+    pub fn tick(&mut self, cpu_cycles: u64) {
+        let dots = cpu_cycles * 3;
+        self.clock_count += dots;
+        self.ppu.tick(dots as u16);
+    }
+
+    /// The bus's running dot-accurate clock: three ticks per CPU cycle
+    /// that has passed through [`Self::tick`], matching the PPU's own dot
+    /// advance one-for-one.
+    pub fn master_clock(&self) -> u64 {
+        self.clock_count
+    }
+
+    /// Registers `mapper` as this bus's source of mapper-driven IRQs. Only
+    /// one can be registered at a time; a later call replaces the earlier
+    /// one.
+    pub fn set_mapper(&mut self, mapper: Box<dyn Mapper>) {
+        self.mapper = Some(mapper);
+    }
+
+    /// Whether the registered mapper (if any) currently wants to assert the
+    /// CPU's IRQ line. `false` with no mapper registered.
+    pub fn mapper_irq_pending(&self) -> bool {
+        self.mapper
+            .as_ref()
+            .is_some_and(|mapper| mapper.irq_pending())
+    }
+
+    /// Acknowledges the registered mapper's IRQ, if any.
+    pub fn mapper_irq_clear(&mut self) {
+        if let Some(mapper) = &mut self.mapper {
+            mapper.irq_clear();
+        }
+    }
+
+    /// Registers `device` to handle reads and writes within `range`,
+    /// overriding whatever the existing address decode would otherwise do
+    /// there. Ranges are checked in registration order; the first match
+    /// wins.
+    pub fn map_device(&mut self, range: RangeInclusive<u16>, device: Box<dyn BusDevice>) {
+        self.devices.push((range, device));
+    }
+
+    fn device_for(&mut self, addr: u16) -> Option<&mut Box<dyn BusDevice>> {
+        self.devices
+            .iter_mut()
+            .find(|(range, _)| range.contains(&addr))
+            .map(|(_, device)| device)
+    }
+
+    /// Addresses written to while [`Self::strict_rom`] is set, in the order
+    /// they occurred.
+    pub fn rom_write_violations(&self) -> &[u16] {
+        &self.rom_write_violations
+    }
+
+    /// Read-only view of zero page (`$0000-$00FF`), for debuggers.
+    pub fn zero_page(&self) -> &[u8] {
+        &self.cpu_vram[0x0000..0x0100]
+    }
+
+    /// Read-only view of the stack page (`$0100-$01FF`), for debuggers.
+    pub fn stack(&self) -> &[u8] {
+        &self.cpu_vram[0x0100..0x0200]
+    }
+
+    /// Full 2KB internal RAM, for [`crate::state::SystemState`] to
+    /// round-trip. See [`Self::zero_page`]/[`Self::stack`] for narrower,
+    /// public debugger views.
+    pub(crate) fn ram(&self) -> &[u8; 2048] {
+        &self.cpu_vram
+    }
+
+    pub(crate) fn set_ram(&mut self, data: [u8; 2048]) {
+        self.cpu_vram = data;
+    }
+
+    pub fn ppu(&mut self) -> &mut NesPpu {
+        &mut self.ppu
+    }
+
+    pub fn joypad1(&mut self) -> &mut Joypad {
+        &mut self.joypad1
+    }
+
+    /// Index into `prg_rom` for a CPU-visible `addr` in `$8000-$FFFF`,
+    /// mirroring a single 16KB bank into both halves of that space. A free
+    /// function (rather than a method) so [`Self::mem_write`]'s bus-conflict
+    /// handling can compute it without a `&self` borrow fighting the
+    /// simultaneous `&mut self.mapper` borrow it also needs.
+    fn prg_rom_index(addr: u16, prg_rom_len: usize) -> usize {
+        let mut addr = addr - PRG_ROM_START;
+        if prg_rom_len == PRG_ROM_BANK_SIZE as usize && addr >= PRG_ROM_BANK_SIZE {
+            addr %= PRG_ROM_BANK_SIZE;
+        }
+        addr as usize
+    }
+
+    /// Carts with a single 16KB PRG ROM bank mirror it into both halves of
+    /// `$8000-$FFFF`.
+    fn read_prg_rom(&self, addr: u16) -> u8 {
+        self.prg_rom[Self::prg_rom_index(addr, self.prg_rom.len())]
+    }
+
+    /// Reads `addr` the way a debugger would: the same value `mem_read`
+    /// would return, but without triggering read side effects like clearing
+    /// vblank or shifting the joypad's button index. Side-effectful
+    /// registers return a best-effort snapshot instead. Devices registered
+    /// via [`Self::map_device`] aren't visible here, since `BusDevice` has
+    /// no side-effect-free read of its own.
+    pub fn peek(&self, addr: u16) -> u8 {
+        match addr {
+            RAM..=RAM_MIRRORS_END => {
+                let mirror_down_addr = addr & 0b0000_0111_1111_1111;
+                self.cpu_vram[mirror_down_addr as usize]
+            }
+            0x2000 | 0x2001 | 0x2003 | 0x2005 | 0x2006 => self.ppu.read_latch(),
+            0x4014 => 0,
+            0x2002 => self.ppu.peek_status(),
+            0x2004 => self.ppu.read_oam_data(),
+            0x2007 => self.ppu.peek_data(),
+            0x2008..=PPU_REGISTERS_MIRRORS_END => {
+                let mirror_down_addr = addr & 0b0010_0000_0000_0111;
+                self.peek(mirror_down_addr)
+            }
+            0x4016 => self.joypad1.peek(),
+            0x4017 | 0x4000..=0x4015 | 0x4018..=0x401f => 0, // APU not yet implemented
+            PRG_ROM_START..=0xffff => self.read_prg_rom(addr),
+            _ => 0, // Cartridge expansion / SRAM space; no mapper uses it yet.
+        }
+    }
+}
+
+impl Clone for Bus {
+    /// Clones everything except custom devices registered via
+    /// [`Self::map_device`] and any mapper registered via [`Self::set_mapper`]:
+    /// neither `BusDevice` nor `Mapper` is required to be cloneable, so a
+    /// cloned bus starts with neither mapped. RAM, the PPU, the cartridge
+    /// and the controller all clone fully.
+    fn clone(&self) -> Self {
+        Bus {
+            cpu_vram: self.cpu_vram,
+            prg_rom: self.prg_rom.clone(),
+            ppu: self.ppu.clone(),
+            joypad1: self.joypad1.clone(),
+            strict_rom: self.strict_rom,
+            rom_write_violations: self.rom_write_violations.clone(),
+            devices: Vec::new(),
+            mapper: None,
+            clock_count: self.clock_count,
+        }
+    }
+}
+
+impl Mem for Bus {
+    #[inline]
+    fn mem_read(&mut self, addr: u16) -> u8 {
+        if let Some(device) = self.device_for(addr) {
+            return device.read(addr);
+        }
+        match addr {
+            RAM..=RAM_MIRRORS_END => {
+                let mirror_down_addr = addr & 0b0000_0111_1111_1111;
+                self.cpu_vram[mirror_down_addr as usize]
+            }
+            // PPUCTRL/PPUMASK/OAMADDR/PPUSCROLL/PPUADDR are write-only;
+            // reading them returns the PPU's I/O bus latch instead.
+            0x2000 | 0x2001 | 0x2003 | 0x2005 | 0x2006 => self.ppu.read_latch(),
+            0x4014 => 0,
+            0x2002 => self.ppu.read_status(),
+            0x2004 => self.ppu.read_oam_data(),
+            0x2007 => self.ppu.read_data(),
+            0x2008..=PPU_REGISTERS_MIRRORS_END => {
+                let mirror_down_addr = addr & 0b0010_0000_0000_0111;
+                self.mem_read(mirror_down_addr)
+            }
+            0x4016 => self.joypad1.read(),
+            0x4017 | 0x4000..=0x4015 | 0x4018..=0x401f => 0, // APU not yet implemented
+            PRG_ROM_START..=0xffff => self.read_prg_rom(addr),
+            _ => {
+                // Cartridge expansion / SRAM space; no mapper uses it yet.
+                0
+            }
+        }
+    }
+
+    #[inline]
+    fn mem_write(&mut self, addr: u16, data: u8) {
+        if let Some(device) = self.device_for(addr) {
+            device.write(addr, data);
+            return;
+        }
+        match addr {
+            RAM..=RAM_MIRRORS_END => {
+                let mirror_down_addr = addr & 0b0000_0111_1111_1111;
+                self.cpu_vram[mirror_down_addr as usize] = data;
+            }
+            0x2000 => self.ppu.write_to_ctrl(data),
+            0x2001 => self.ppu.write_to_mask(data),
+            0x2003 => self.ppu.write_to_oam_addr(data),
+            0x2004 => self.ppu.write_to_oam_data(data),
+            0x2005 => self.ppu.write_to_scroll(data),
+            0x2006 => self.ppu.write_to_ppu_addr(data),
+            0x2007 => self.ppu.write_to_data(data),
+            0x4014 => {
+                // OAM DMA lands separately.
+            }
+            0x2008..=PPU_REGISTERS_MIRRORS_END => {
+                let mirror_down_addr = addr & 0b0010_0000_0000_0111;
+                self.mem_write(mirror_down_addr, data);
+            }
+            0x4016 => self.joypad1.write(data),
+            0x4017 | 0x4000..=0x4015 | 0x4018..=0x401f => {
+                // APU not yet implemented.
+            }
+            // PRG ROM is read-only; bank-switching mappers trap this
+            // instead, latching whatever effectively reached the bus (see
+            // `resolve_bus_conflict`) into a bank-select register. In
+            // strict mode we also record the offending address.
+            PRG_ROM_START..=0xffff => {
+                if self.strict_rom {
+                    self.rom_write_violations.push(addr);
+                }
+                if let Some(mapper) = &mut self.mapper {
+                    let effective = resolve_bus_conflict(
+                        data,
+                        self.prg_rom[Self::prg_rom_index(addr, self.prg_rom.len())],
+                        mapper.has_bus_conflicts(),
+                    );
+                    mapper.notify_prg_write(addr, effective);
+                }
+            }
+            _ => {
+                // Cartridge expansion / SRAM space; no mapper uses it yet.
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ppu::Mirroring;
+
+    fn test_rom() -> Rom {
+        Rom {
+            prg_rom: vec![0; 0x4000],
+            chr_rom: vec![0; 0x2000],
+            mapper: 0,
+            mirroring: Mirroring::Horizontal,
+            chr_ram: false,
+        }
+    }
+
+    #[test]
+    fn test_new_with_ram_fill_powers_on_with_chosen_value() {
+        let mut bus = Bus::new_with_ram_fill(test_rom(), 0xff);
+        assert_eq!(bus.mem_read(0x0042), 0xff);
+    }
+
+    #[test]
+    fn test_new_defaults_to_zero_filled_ram() {
+        let mut bus = Bus::new(test_rom());
+        assert_eq!(bus.mem_read(0x0042), 0x00);
+    }
+
+    #[test]
+    fn test_strict_rom_records_writes_into_rom_space() {
+        let mut bus = Bus::new(test_rom());
+        bus.strict_rom = true;
+        bus.mem_write(0x9000, 0xff);
+        assert_eq!(bus.rom_write_violations(), &[0x9000]);
+    }
+
+    #[test]
+    fn test_rom_writes_are_silently_ignored_outside_strict_mode() {
+        let mut bus = Bus::new(test_rom());
+        bus.mem_write(0x9000, 0xff);
+        assert!(bus.rom_write_violations().is_empty());
+    }
+
+    #[test]
+    fn test_read16_reads_the_reset_vector_through_device_decoded_prg_rom_access() {
+        let mut rom = test_rom();
+        rom.prg_rom[0x3ffc] = 0x34; // mirrors down from $FFFC
+        rom.prg_rom[0x3ffd] = 0x12;
+        let mut bus = Bus::new(rom);
+
+        assert_eq!(bus.read16(0xfffc), 0x1234);
+    }
+
+    #[test]
+    fn test_resolve_bus_conflict_ands_written_value_with_rom_byte_when_enabled() {
+        let effective_bank = resolve_bus_conflict(0b1100_1100, 0b1010_1010, true);
+        assert_eq!(effective_bank, 0b1000_1000);
+    }
+
+    #[test]
+    fn test_resolve_bus_conflict_passes_the_written_value_through_when_disabled() {
+        assert_eq!(resolve_bus_conflict(0xff, 0x00, false), 0xff);
+    }
+
+    /// A stub bank-switching mapper (UxROM/CNROM-style) that records
+    /// whatever byte [`Mapper::notify_prg_write`] hands it into a shared
+    /// cell, so a test can inspect it after the mapper's been moved into a
+    /// `Box<dyn Mapper>` registered on the bus.
+    struct StubBusConflictMapper {
+        has_bus_conflicts: bool,
+        last_write: std::rc::Rc<std::cell::RefCell<Option<(u16, u8)>>>,
+    }
+
+    impl Mapper for StubBusConflictMapper {
+        fn has_bus_conflicts(&self) -> bool {
+            self.has_bus_conflicts
+        }
+
+        fn notify_prg_write(&mut self, addr: u16, value: u8) {
+            *self.last_write.borrow_mut() = Some((addr, value));
+        }
+    }
+
+    #[test]
+    fn test_mem_write_applies_the_bus_conflict_and_before_notifying_a_conflicted_mapper() {
+        let mut rom = test_rom();
+        rom.prg_rom[0] = 0b1010_1010; // the ROM byte driving the bus at $8000
+        let mut bus = Bus::new(rom);
+        let last_write = std::rc::Rc::new(std::cell::RefCell::new(None));
+        bus.set_mapper(Box::new(StubBusConflictMapper {
+            has_bus_conflicts: true,
+            last_write: last_write.clone(),
+        }));
+
+        bus.mem_write(0x8000, 0b1100_1100);
+
+        assert_eq!(*last_write.borrow(), Some((0x8000, 0b1000_1000)));
+    }
+
+    #[test]
+    fn test_mem_write_passes_the_written_byte_through_when_the_mapper_has_no_bus_conflicts() {
+        let mut rom = test_rom();
+        rom.prg_rom[0] = 0x00; // would zero out the write if (wrongly) ANDed
+        let mut bus = Bus::new(rom);
+        let last_write = std::rc::Rc::new(std::cell::RefCell::new(None));
+        bus.set_mapper(Box::new(StubBusConflictMapper {
+            has_bus_conflicts: false,
+            last_write: last_write.clone(),
+        }));
+
+        bus.mem_write(0x8000, 0xff);
+
+        assert_eq!(*last_write.borrow(), Some((0x8000, 0xff)));
+    }
+
+    #[test]
+    fn test_chr_ram_cart_round_trips_a_tile_write_through_ppudata() {
+        let rom = Rom {
+            prg_rom: vec![0; 0x4000],
+            chr_rom: vec![0; 0x2000],
+            mapper: 0,
+            mirroring: Mirroring::Horizontal,
+            chr_ram: true,
+        };
+        let mut bus = Bus::new(rom);
+
+        bus.mem_write(0x2006, 0x00); // PPUADDR high byte
+        bus.mem_write(0x2006, 0x10); // PPUADDR low byte: $0010, inside pattern tables
+        bus.mem_write(0x2007, 0x5a); // PPUDATA: write a tile byte
+
+        bus.mem_write(0x2006, 0x00);
+        bus.mem_write(0x2006, 0x10);
+        bus.mem_read(0x2007); // primes the read buffer from $0010
+        bus.mem_write(0x2006, 0x00);
+        bus.mem_write(0x2006, 0x10);
+        assert_eq!(bus.mem_read(0x2007), 0x5a);
+    }
+
+    #[test]
+    fn test_oamaddr_oamdata_write_sprite_bytes_one_at_a_time() {
+        let mut bus = Bus::new(test_rom());
+        bus.mem_write(0x2003, 0x04); // OAMADDR
+        bus.mem_write(0x2004, 0x11);
+        bus.mem_write(0x2004, 0x22);
+
+        bus.mem_write(0x2003, 0x04);
+        assert_eq!(bus.mem_read(0x2004), 0x11);
+        bus.mem_write(0x2003, 0x05);
+        assert_eq!(bus.mem_read(0x2004), 0x22);
+    }
+
+    #[test]
+    fn test_peek_ppustatus_does_not_clear_vblank_unlike_mem_read() {
+        let mut bus = Bus::new(test_rom());
+        bus.ppu().set_vblank_status(true);
+
+        assert_eq!(bus.peek(0x2002) & 0b1000_0000, 0b1000_0000);
+        assert_eq!(bus.peek(0x2002) & 0b1000_0000, 0b1000_0000); // still set
+
+        assert_eq!(bus.mem_read(0x2002) & 0b1000_0000, 0b1000_0000); // cleared after this read
+        assert_eq!(bus.peek(0x2002) & 0b1000_0000, 0);
+    }
+
+    #[test]
+    fn test_reading_a_write_only_ppu_register_returns_the_io_latch() {
+        let mut bus = Bus::new(test_rom());
+
+        bus.mem_write(0x2000, 0x42); // PPUCTRL: write-only
+        assert_eq!(bus.mem_read(0x2000), 0x42);
+        assert_eq!(bus.peek(0x2000), 0x42);
+    }
+
+    struct EchoDevice {
+        memory: [u8; 0x1000],
+    }
+
+    impl EchoDevice {
+        fn new() -> Self {
+            EchoDevice {
+                memory: [0; 0x1000],
+            }
+        }
+    }
+
+    impl BusDevice for EchoDevice {
+        fn read(&mut self, addr: u16) -> u8 {
+            self.memory[(addr - 0x5000) as usize]
+        }
+
+        fn write(&mut self, addr: u16, data: u8) {
+            self.memory[(addr - 0x5000) as usize] = data;
+        }
+    }
+
+    #[test]
+    fn test_map_device_dispatches_reads_and_writes_in_its_range() {
+        let mut bus = Bus::new(test_rom());
+        bus.map_device(0x5000..=0x5fff, Box::new(EchoDevice::new()));
+
+        bus.mem_write(0x5042, 0x99);
+        assert_eq!(bus.mem_read(0x5042), 0x99);
+
+        // Addresses outside the mapped range still go through the normal
+        // decode, untouched by the device.
+        assert_eq!(bus.mem_read(0x0042), 0x00);
+    }
+
+    /// A stub scanline-counter mapper: asserts IRQ once [`Self::notify_ppu_read`]
+    /// has been called `reads_remaining` times, as a real MMC3-style counter
+    /// would once it ticks down to zero.
+    struct StubIrqMapper {
+        reads_remaining: u8,
+        pending: bool,
+    }
+
+    impl Mapper for StubIrqMapper {
+        fn irq_pending(&self) -> bool {
+            self.pending
+        }
+
+        fn irq_clear(&mut self) {
+            self.pending = false;
+        }
+
+        fn notify_ppu_read(&mut self, _addr: u16) {
+            self.reads_remaining = self.reads_remaining.saturating_sub(1);
+            if self.reads_remaining == 0 {
+                self.pending = true;
+            }
+        }
+    }
+
+    #[test]
+    fn test_mapper_irq_pending_relays_the_registered_mapper() {
+        let mut bus = Bus::new(test_rom());
+        assert!(!bus.mapper_irq_pending());
+
+        let mut mapper = StubIrqMapper {
+            reads_remaining: 3,
+            pending: false,
+        };
+        for _ in 0..3 {
+            mapper.notify_ppu_read(0x1000);
+        }
+        bus.set_mapper(Box::new(mapper));
+
+        assert!(bus.mapper_irq_pending());
+        bus.mapper_irq_clear();
+        assert!(!bus.mapper_irq_pending());
+    }
+
+    #[test]
+    fn test_cpu_services_an_irq_asserted_by_the_registered_mapper() {
+        use crate::cpu::{CpuFlags, CPU};
+
+        let mut rom = test_rom();
+        rom.prg_rom[0x3ffc] = 0x00; // reset vector -> $8000
+        rom.prg_rom[0x3ffd] = 0x80;
+        rom.prg_rom[0x3ffe] = 0x00; // IRQ/BRK vector -> $9000
+        rom.prg_rom[0x3fff] = 0x90;
+        rom.prg_rom[0] = 0xea; // NOP at $8000
+        let mut bus = Bus::new(rom);
+        bus.set_mapper(Box::new(StubIrqMapper {
+            reads_remaining: 0,
+            pending: true,
+        }));
+
+        let mut cpu = CPU::new(bus);
+        cpu.power_on();
+        cpu.status.remove(CpuFlags::INTERRUPT_DISABLE);
+
+        if cpu.mem.mapper_irq_pending() {
+            cpu.request_irq();
+        }
+        cpu.step(); // executes the NOP, then services the pending IRQ
+
+        assert_eq!(cpu.program_counter, 0x9000);
+    }
+
+    #[test]
+    fn test_master_clock_tracks_three_dots_per_cpu_cycle_across_several_instructions() {
+        use crate::cpu::CPU;
+
+        let mut rom = test_rom();
+        rom.prg_rom[0x3ffc] = 0x00; // reset vector -> $8000
+        rom.prg_rom[0x3ffd] = 0x80;
+        rom.prg_rom[0] = 0xea; // NOP
+        rom.prg_rom[1] = 0xea; // NOP
+        rom.prg_rom[2] = 0xea; // NOP
+        let bus = Bus::new(rom);
+        let mut cpu = CPU::new(bus);
+        cpu.power_on();
+
+        let start_cycles = cpu.cycles;
+        for _ in 0..3 {
+            let cycles_before = cpu.cycles;
+            cpu.step();
+            let elapsed = cpu.cycles - cycles_before;
+            cpu.mem.tick(elapsed);
+        }
+        let total_cycles = cpu.cycles - start_cycles;
+
+        assert_eq!(cpu.mem.master_clock(), total_cycles * 3);
+        assert_eq!(
+            cpu.mem.ppu().ppu_position(),
+            (0, cpu.mem.master_clock() as u16)
+        );
+    }
+}
@@ -0,0 +1,260 @@
+//! A tiny PRG-ROM disassembler, built on [`CPU::resolved_address`] so
+//! indexed/indirect operands get annotated with their effective address,
+//! the same as a real debugger would show. `examples/disasm.rs` is a thin
+//! CLI wrapper around [`disassemble`].
+//!
+//! [`InstructionStream`] is the non-CPU, pure-decode counterpart: it just
+//! walks a byte slice opcode-by-opcode without resolving operand addresses,
+//! for tooling that wants instruction boundaries without a live machine.
+
+use crate::bus::Bus;
+use crate::cpu::CPU;
+use crate::mem::Mem;
+use crate::opcodes::{is_branch, AddressingMode, OpCode, OPCODES_TABLE};
+
+/// One instruction decoded by [`InstructionStream`]: its offset into the
+/// original slice, its raw bytes, and the matched [`OpCode`] if the first
+/// byte was recognized.
+///
+/// `opcode` is `None` for an unrecognized byte, or for a recognized opcode
+/// whose operand bytes would run past the end of the slice — in both cases
+/// `bytes` holds just the single byte at `offset`, matching [`disassemble`]'s
+/// `.byte $XX` fallback.
+#[derive(Clone)]
+pub struct DecodedInstruction<'a> {
+    pub offset: usize,
+    pub bytes: &'a [u8],
+    pub opcode: Option<&'static OpCode>,
+}
+
+/// Iterates over `code`, yielding a [`DecodedInstruction`] per step and
+/// advancing by that instruction's length. Never panics on truncated input:
+/// an opcode whose declared length would read past the end of the slice is
+/// reported as a one-byte unrecognized instruction instead.
+pub struct InstructionStream<'a> {
+    code: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> InstructionStream<'a> {
+    pub fn new(code: &'a [u8]) -> Self {
+        InstructionStream { code, offset: 0 }
+    }
+}
+
+impl<'a> Iterator for InstructionStream<'a> {
+    type Item = DecodedInstruction<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let byte = *self.code.get(self.offset)?;
+        let opcode = OPCODES_TABLE[byte as usize]
+            .filter(|opcode| self.offset + opcode.len as usize <= self.code.len());
+        let len = opcode.map_or(1, |opcode| opcode.len as usize);
+
+        let instr = DecodedInstruction {
+            offset: self.offset,
+            bytes: &self.code[self.offset..self.offset + len],
+            opcode,
+        };
+        self.offset += len;
+        Some(instr)
+    }
+}
+
+/// Disassembles consecutive instructions starting at `start`, stopping once
+/// the program counter reaches `end` (exclusive). Unrecognized opcodes are
+/// emitted as a single-byte `.byte $XX` directive and decoding resumes at
+/// the next byte, so a run of PRG-ROM containing data rather than code
+/// doesn't abort the dump.
+pub fn disassemble(cpu: &mut CPU<Bus>, start: u16, end: u16) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut pc = start;
+    while pc < end {
+        let code = cpu.mem_read(pc);
+        match OPCODES_TABLE[code as usize] {
+            Some(opcode) => {
+                let bytes: Vec<u8> = (0..opcode.len as u16)
+                    .map(|i| cpu.mem_read(pc.wrapping_add(i)))
+                    .collect();
+                let hex = bytes
+                    .iter()
+                    .map(|b| format!("{b:02X}"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                let operand = format_operand(cpu, opcode, pc);
+                lines.push(
+                    format!("${pc:04X}  {hex:<8}  {} {operand}", opcode.mnemonic)
+                        .trim_end()
+                        .to_string(),
+                );
+                pc = pc.wrapping_add(opcode.len as u16);
+            }
+            None => {
+                lines.push(format!("${pc:04X}  {code:02X}        .byte ${code:02X}"));
+                pc = pc.wrapping_add(1);
+            }
+        }
+    }
+    lines
+}
+
+/// Renders `opcode`'s operand in assembler syntax, annotating indexed and
+/// indirect modes with their resolved effective address (e.g.
+/// `$0200,X @ $0205`) via [`CPU::resolved_address`], and relative branches
+/// with their resolved absolute target (e.g. `$8004`) instead of the raw
+/// signed offset byte.
+fn format_operand(cpu: &mut CPU<Bus>, opcode: &OpCode, opcode_pc: u16) -> String {
+    let operand_pc = opcode_pc.wrapping_add(1);
+    let mode = opcode.mode;
+    match mode {
+        AddressingMode::Immediate => format!("#${:02X}", cpu.mem_read(operand_pc)),
+        AddressingMode::ZeroPage => format!("${:02X}", cpu.mem_read(operand_pc)),
+        AddressingMode::Accumulator => "A".to_string(),
+        AddressingMode::NoneAddressing if is_branch(opcode.mnemonic) => {
+            let offset = cpu.mem_read(operand_pc) as i8;
+            let target = operand_pc.wrapping_add(1).wrapping_add(offset as u16);
+            format!("${target:04X}")
+        }
+        AddressingMode::NoneAddressing => String::new(),
+        AddressingMode::ZeroPage_X | AddressingMode::ZeroPage_Y | AddressingMode::Indirect_X => {
+            let base = cpu.mem_read(operand_pc);
+            let resolved = cpu.resolved_address(&mode, operand_pc);
+            match mode {
+                AddressingMode::ZeroPage_X => format!("${base:02X},X @ ${resolved:04X}"),
+                AddressingMode::ZeroPage_Y => format!("${base:02X},Y @ ${resolved:04X}"),
+                _ => format!("(${base:02X},X) @ ${resolved:04X}"),
+            }
+        }
+        AddressingMode::Indirect_Y => {
+            let base = cpu.mem_read(operand_pc);
+            let resolved = cpu.resolved_address(&mode, operand_pc);
+            format!("(${base:02X}),Y @ ${resolved:04X}")
+        }
+        AddressingMode::Absolute => format!("${:04X}", cpu.mem_read_u16(operand_pc)),
+        AddressingMode::Absolute_X | AddressingMode::Absolute_Y => {
+            let base = cpu.mem_read_u16(operand_pc);
+            let resolved = cpu.resolved_address(&mode, operand_pc);
+            let reg = if mode == AddressingMode::Absolute_X {
+                'X'
+            } else {
+                'Y'
+            };
+            format!("${base:04X},{reg} @ ${resolved:04X}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::rom::Rom;
+
+    /// A minimal iNES header plus a 16KB PRG-ROM bank whose reset vector
+    /// points at `program`, written straight into the bank at `$8000`
+    /// (PRG ROM is read-only to `mem_write`, so tests needing known
+    /// instructions at a known address build the ROM image directly
+    /// instead).
+    fn rom_cpu_with_program(program: &[u8]) -> CPU<Bus> {
+        let mut raw = vec![0u8; 16 + 0x4000 + 0x2000];
+        raw[0..4].copy_from_slice(b"NES\x1a");
+        raw[4] = 1; // 1 x 16KB PRG-ROM bank
+        raw[5] = 1; // 1 x 8KB CHR-ROM bank
+
+        let prg_start = 16;
+        let reset_target = 0x8000u16;
+        let offset = (reset_target - 0x8000) as usize;
+        raw[prg_start + offset..prg_start + offset + program.len()].copy_from_slice(program);
+
+        // The reset vector lives at the top of the 16KB bank, $BFFC-$BFFD,
+        // which NROM mirrors onto $FFFC-$FFFD.
+        let vector_offset = 0x3ffc;
+        raw[prg_start + vector_offset..prg_start + vector_offset + 2]
+            .copy_from_slice(&reset_target.to_le_bytes());
+
+        let rom: Rom = raw.try_into().unwrap();
+        let mut cpu = CPU::new(Bus::new(rom));
+        cpu.power_on();
+        cpu
+    }
+
+    /// `LDA #$05`, `TAX`, `BRK`, followed by one unrecognized byte to
+    /// exercise the `.byte` fallback.
+    fn tiny_rom_cpu() -> CPU<Bus> {
+        rom_cpu_with_program(&[0xa9, 0x05, 0xaa, 0x00, 0xff])
+    }
+
+    #[test]
+    fn test_disassemble_decodes_known_instructions_and_falls_back_on_unknown_bytes() {
+        let mut cpu = tiny_rom_cpu();
+        let lines = disassemble(&mut cpu, 0x8000, 0x8005);
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].contains("LDA #$05"));
+        assert!(lines[1].contains("TAX"));
+        assert!(lines[2].contains("BRK"));
+        assert!(lines[3].contains(".byte $FF"));
+    }
+
+    #[test]
+    fn test_disassemble_from_reset_vector_produces_well_formed_non_empty_lines() {
+        let mut cpu = tiny_rom_cpu();
+        let start = cpu.program_counter;
+        let lines = disassemble(&mut cpu, start, start.wrapping_add(5));
+
+        assert!(!lines.is_empty());
+        for line in &lines {
+            assert!(!line.is_empty());
+            assert!(line.starts_with('$'));
+        }
+    }
+
+    #[test]
+    fn test_disassemble_annotates_a_forward_branch_with_its_absolute_target() {
+        // BNE +$02 at $8000: target = $8000 + 2 (opcode+operand) + 2 = $8004.
+        let mut cpu = rom_cpu_with_program(&[0xd0, 0x02]);
+        let lines = disassemble(&mut cpu, 0x8000, 0x8002);
+
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("BNE $8004"), "{}", lines[0]);
+    }
+
+    #[test]
+    fn test_disassemble_annotates_a_backward_branch_with_its_absolute_target() {
+        // BNE -$04 at $8000: target = $8000 + 2 - 4 = $7FFE.
+        let mut cpu = rom_cpu_with_program(&[0xd0, 0xfc]);
+        let lines = disassemble(&mut cpu, 0x8000, 0x8002);
+
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].contains("BNE $7FFE"), "{}", lines[0]);
+    }
+
+    #[test]
+    fn test_instruction_stream_collects_decoded_instructions_with_offsets() {
+        // LDA #$05; TAX; BRK.
+        let code = [0xa9, 0x05, 0xaa, 0x00];
+        let decoded: Vec<DecodedInstruction> = InstructionStream::new(&code).collect();
+
+        assert_eq!(decoded.len(), 3);
+        assert_eq!(decoded[0].offset, 0);
+        assert_eq!(decoded[0].opcode.unwrap().mnemonic, "LDA");
+        assert_eq!(decoded[0].bytes, &[0xa9, 0x05]);
+        assert_eq!(decoded[1].offset, 2);
+        assert_eq!(decoded[1].opcode.unwrap().mnemonic, "TAX");
+        assert_eq!(decoded[2].offset, 3);
+        assert_eq!(decoded[2].opcode.unwrap().mnemonic, "BRK");
+    }
+
+    #[test]
+    fn test_instruction_stream_truncated_opcode_near_end_yields_byte_marker() {
+        // LDA absolute needs 3 bytes but only 2 remain: should fall back to
+        // a single unrecognized byte rather than reading past the slice.
+        let code = [0xad, 0xff];
+        let decoded: Vec<DecodedInstruction> = InstructionStream::new(&code).collect();
+
+        assert_eq!(decoded.len(), 2);
+        assert!(decoded[0].opcode.is_none());
+        assert_eq!(decoded[0].bytes, &[0xad]);
+        assert!(decoded[1].opcode.is_none());
+        assert_eq!(decoded[1].bytes, &[0xff]);
+    }
+}
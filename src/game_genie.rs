@@ -0,0 +1,105 @@
+//! Game Genie–style cheat codes, decoded via [`CPU::apply_game_genie`].
+//!
+//! A code is six or eight letters drawn from the classic Game Genie
+//! alphabet (`APZLGITYEOXUKSVN`, each letter a 4-bit nibble), packing an
+//! address, a value to write there, and — for eight-letter codes only — a
+//! compare byte the current value must match before the write happens.
+//! This uses the real letter alphabet but packs nibbles straight into
+//! fields rather than reproducing the original cartridge's scrambled-bit
+//! cipher, so codes from a real Game Genie's code book won't decode to
+//! the same address here; the format (length, alphabet, fields) is what
+//! this module is reproducing, not a specific game's existing codes.
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+const LETTERS: &[u8; 16] = b"APZLGITYEOXUKSVN";
+
+/// A decoded Game Genie–style code, ready to apply via
+/// [`crate::cpu::CPU::apply_game_genie`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GameGenieCode {
+    pub address: u16,
+    pub value: u8,
+    /// Only present for eight-letter codes; the byte already at `address`
+    /// must equal this for the patch to apply.
+    pub compare: Option<u8>,
+}
+
+fn nibble_for(letter: u8) -> Result<u16, String> {
+    LETTERS
+        .iter()
+        .position(|&l| l == letter)
+        .map(|i| i as u16)
+        .ok_or_else(|| format!("'{}' is not a Game Genie letter", letter as char))
+}
+
+/// Decodes a 6- or 8-character Game Genie–style `code` into its
+/// address/value/compare fields. See the module docs for how letters map
+/// to those fields.
+pub fn decode(code: &str) -> Result<GameGenieCode, String> {
+    let n: Vec<u16> = code
+        .trim()
+        .bytes()
+        .map(|b| nibble_for(b.to_ascii_uppercase()))
+        .collect::<Result<_, _>>()?;
+
+    let compare = match n.len() {
+        6 => None,
+        8 => Some(((n[6] << 4) | n[7]) as u8),
+        len => {
+            return Err(format!(
+                "Game Genie codes are 6 or 8 letters long, got {len}"
+            ))
+        }
+    };
+
+    let address = 0x8000 | (n[0] << 11) | (n[1] << 7) | (n[2] << 3) | (n[3] >> 1);
+    let value = ((n[4] << 4) | n[5]) as u8;
+
+    Ok(GameGenieCode {
+        address,
+        value,
+        compare,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decode_a_six_char_code_has_no_compare_byte() {
+        // A=0, P=1, Z=2, L=3, G=4, I=5
+        let patch = decode("APZLGI").unwrap();
+        let n: [u16; 6] = [0, 1, 2, 3, 4, 5];
+        let expected_address = 0x8000 | (n[0] << 11) | (n[1] << 7) | (n[2] << 3) | (n[3] >> 1);
+        let expected_value = ((n[4] << 4) | n[5]) as u8;
+        assert_eq!(patch.address, expected_address);
+        assert_eq!(patch.value, expected_value);
+        assert_eq!(patch.compare, None);
+    }
+
+    #[test]
+    fn test_decode_an_eight_char_code_carries_a_compare_byte() {
+        // A=0, P=1, Z=2, L=3, G=4, I=5, T=6, Y=7
+        let patch = decode("APZLGITY").unwrap();
+        let n: [u16; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+        let expected_address = 0x8000 | (n[0] << 11) | (n[1] << 7) | (n[2] << 3) | (n[3] >> 1);
+        let expected_value = ((n[4] << 4) | n[5]) as u8;
+        let expected_compare = ((n[6] << 4) | n[7]) as u8;
+        assert_eq!(patch.address, expected_address);
+        assert_eq!(patch.value, expected_value);
+        assert_eq!(patch.compare, Some(expected_compare));
+    }
+
+    #[test]
+    fn test_decode_rejects_a_letter_outside_the_game_genie_alphabet() {
+        assert!(decode("APZLGB").is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_the_wrong_length() {
+        assert!(decode("APZLG").is_err());
+    }
+}
@@ -0,0 +1,239 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// The eight buttons on a standard NES controller, in the order the
+    /// hardware shifts them out: A, B, Select, Start, Up, Down, Left, Right.
+    #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+    pub struct JoypadButton: u8 {
+        const BUTTON_A  = 0b0000_0001;
+        const BUTTON_B  = 0b0000_0010;
+        const SELECT    = 0b0000_0100;
+        const START     = 0b0000_1000;
+        const UP        = 0b0001_0000;
+        const DOWN      = 0b0010_0000;
+        const LEFT      = 0b0100_0000;
+        const RIGHT     = 0b1000_0000;
+    }
+}
+
+/// Per-button auto-fire state, configured by [`Joypad::set_turbo`] and
+/// advanced once per frame by [`Joypad::tick_frame`].
+#[derive(Clone, Copy, Default)]
+struct TurboState {
+    rate_frames: u8,
+    held: bool,
+    frame_counter: u8,
+}
+
+/// A standard NES controller, as seen through `$4016`/`$4017`: writing bit 0
+/// sets the strobe (continuously reloading button 0), and reading while
+/// strobe is clear shifts out one button per read, A first.
+#[derive(Clone)]
+pub struct Joypad {
+    strobe: bool,
+    button_index: u8,
+    status: JoypadButton,
+    /// One turbo slot per button bit, indexed by its bit position (A = 0,
+    /// B = 1, ... Right = 7). `None` means that button has no turbo
+    /// configured and behaves as a normal digital button.
+    turbo: [Option<TurboState>; 8],
+}
+
+impl Joypad {
+    pub fn new() -> Self {
+        Joypad {
+            strobe: false,
+            button_index: 0,
+            status: JoypadButton::empty(),
+            turbo: [None; 8],
+        }
+    }
+
+    /// Index into [`Self::turbo`] for a single-bit `button`. `button` must
+    /// be exactly one of the eight [`JoypadButton`] flags.
+    fn turbo_slot(button: JoypadButton) -> usize {
+        button.bits().trailing_zeros() as usize
+    }
+
+    /// Configures `button` for auto-fire, alternating pressed/released every
+    /// `rate_frames` frames (of [`Self::tick_frame`]) while held via
+    /// [`Self::set_turbo_held`]. A `rate_frames` of 0 is treated as 1 (every
+    /// frame).
+    pub fn set_turbo(&mut self, button: JoypadButton, rate_frames: u8) {
+        self.turbo[Self::turbo_slot(button)] = Some(TurboState {
+            rate_frames: rate_frames.max(1),
+            held: false,
+            frame_counter: 0,
+        });
+    }
+
+    /// Marks whether `button`'s turbo is currently "held down" by the
+    /// player. Has no effect unless `button` was configured via
+    /// [`Self::set_turbo`] first. Releasing clears the button's reported
+    /// state immediately rather than leaving it pressed mid-cycle.
+    pub fn set_turbo_held(&mut self, button: JoypadButton, held: bool) {
+        if let Some(turbo) = &mut self.turbo[Self::turbo_slot(button)] {
+            turbo.held = held;
+            turbo.frame_counter = 0;
+            // Apply immediately so the button reads as pressed as soon as it's
+            // held, rather than waiting for the next tick_frame().
+            self.status.set(button, held);
+        }
+    }
+
+    /// Advances every configured turbo button by one frame, toggling its
+    /// reported pressed state every `rate_frames` frames while held. Frontends
+    /// should call this once per rendered frame (e.g. alongside
+    /// [`crate::nes::Nes::step_frame`]).
+    pub fn tick_frame(&mut self) {
+        for (slot, turbo) in self.turbo.iter_mut().enumerate() {
+            let Some(turbo) = turbo else { continue };
+            if !turbo.held {
+                continue;
+            }
+            turbo.frame_counter += 1;
+            if turbo.frame_counter >= turbo.rate_frames * 2 {
+                turbo.frame_counter = 0;
+            }
+            let button = JoypadButton::from_bits_truncate(1 << slot);
+            let pressed = (turbo.frame_counter / turbo.rate_frames) % 2 == 0;
+            self.status.set(button, pressed);
+        }
+    }
+
+    pub fn write(&mut self, data: u8) {
+        self.strobe = data & 1 == 1;
+        if self.strobe {
+            self.button_index = 0;
+        }
+    }
+
+    pub fn read(&mut self) -> u8 {
+        if self.button_index > 7 {
+            return 1;
+        }
+        let response = (self.status.bits() >> self.button_index) & 1;
+        if !self.strobe {
+            self.button_index += 1;
+        }
+        response
+    }
+
+    /// Like [`Self::read`], but without shifting to the next button — for
+    /// debuggers that must not perturb controller state.
+    pub fn peek(&self) -> u8 {
+        if self.button_index > 7 {
+            return 1;
+        }
+        (self.status.bits() >> self.button_index) & 1
+    }
+
+    pub fn set_button_pressed_status(&mut self, button: JoypadButton, pressed: bool) {
+        self.status.set(button, pressed);
+    }
+
+    /// Raw shift-register state, for [`crate::state::SystemState`] to
+    /// round-trip. Turbo configuration isn't included — it's a per-session
+    /// input setting a frontend re-applies on load, not state a game
+    /// observes, so leaving it out doesn't affect determinism.
+    pub(crate) fn shift_register_snapshot(&self) -> (bool, u8, u8) {
+        (self.strobe, self.button_index, self.status.bits())
+    }
+
+    pub(crate) fn restore_shift_register_snapshot(
+        &mut self,
+        (strobe, button_index, status_bits): (bool, u8, u8),
+    ) {
+        self.strobe = strobe;
+        self.button_index = button_index;
+        self.status = JoypadButton::from_bits_truncate(status_bits);
+    }
+}
+
+impl Default for Joypad {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_shifts_out_buttons_a_first() {
+        let mut joypad = Joypad::new();
+        joypad.set_button_pressed_status(JoypadButton::BUTTON_A, true);
+        joypad.set_button_pressed_status(JoypadButton::RIGHT, true);
+        joypad.write(1);
+        joypad.write(0);
+
+        assert_eq!(joypad.read(), 1); // A
+        for _ in 0..6 {
+            assert_eq!(joypad.read(), 0);
+        }
+        assert_eq!(joypad.read(), 1); // RIGHT
+    }
+
+    #[test]
+    fn test_strobe_high_keeps_reloading_button_a() {
+        let mut joypad = Joypad::new();
+        joypad.set_button_pressed_status(JoypadButton::BUTTON_A, true);
+        joypad.write(1); // strobe stays high
+        assert_eq!(joypad.read(), 1);
+        assert_eq!(joypad.read(), 1);
+    }
+
+    #[test]
+    fn test_peek_does_not_shift_the_button_index() {
+        let mut joypad = Joypad::new();
+        joypad.set_button_pressed_status(JoypadButton::BUTTON_A, true);
+        joypad.write(1);
+        joypad.write(0);
+
+        assert_eq!(joypad.peek(), 1); // A
+        assert_eq!(joypad.peek(), 1); // still A, unlike read()
+        assert_eq!(joypad.read(), 1); // A, now actually shifts
+        assert_eq!(joypad.peek(), 0); // B next
+    }
+
+    #[test]
+    fn test_turbo_alternates_button_state_at_the_configured_rate() {
+        let mut joypad = Joypad::new();
+        joypad.set_turbo(JoypadButton::BUTTON_A, 2);
+        joypad.set_turbo_held(JoypadButton::BUTTON_A, true);
+
+        // Pressed for the first 2 frames, released for the next 2, repeat.
+        let expected = [true, true, false, false, true, true, false, false];
+        for want_pressed in expected {
+            joypad.write(1);
+            joypad.write(0);
+            assert_eq!(joypad.read(), want_pressed as u8);
+            joypad.tick_frame();
+        }
+    }
+
+    #[test]
+    fn test_turbo_releasing_held_immediately_clears_the_button() {
+        let mut joypad = Joypad::new();
+        joypad.set_turbo(JoypadButton::BUTTON_A, 2);
+        joypad.set_turbo_held(JoypadButton::BUTTON_A, true);
+        joypad.tick_frame();
+
+        joypad.set_turbo_held(JoypadButton::BUTTON_A, false);
+        joypad.write(1);
+        joypad.write(0);
+        assert_eq!(joypad.read(), 0);
+    }
+
+    #[test]
+    fn test_reads_past_eighth_button_return_one() {
+        let mut joypad = Joypad::new();
+        joypad.write(1);
+        joypad.write(0);
+        for _ in 0..8 {
+            joypad.read();
+        }
+        assert_eq!(joypad.read(), 1);
+    }
+}
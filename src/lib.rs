@@ -0,0 +1,57 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+// Always linked, `std` or not (`std` itself depends on `alloc`), so the core
+// modules below can use `alloc::{vec::Vec, string::String, ...}` uniformly
+// instead of switching import paths per build configuration.
+extern crate alloc;
+
+// The CPU/opcode/memory core: `core`+`alloc` only, so it builds under
+// `#![no_std]` (see the `std` feature in Cargo.toml) for embedding in a
+// frontend that doesn't have a filesystem or OS threads (WASM, a
+// microcontroller, ...).
+pub mod asm;
+pub mod cpu;
+pub mod game_genie;
+pub mod mem;
+pub mod opcodes;
+
+// Everything past here reaches for `std` (file I/O, `HashMap`, wall-clock
+// time) or simply isn't part of the embeddable core.
+#[cfg(feature = "std")]
+pub mod apu;
+#[cfg(feature = "std")]
+pub mod bus;
+#[cfg(feature = "std")]
+pub mod disasm;
+#[cfg(feature = "std")]
+pub mod joypad;
+#[cfg(feature = "std")]
+pub mod mapper;
+#[cfg(feature = "std")]
+pub mod nes;
+#[cfg(feature = "std")]
+pub mod ppu;
+#[cfg(feature = "std")]
+pub mod rom;
+#[cfg(feature = "std")]
+pub mod state;
+
+/// Compile-only check that the `core`+`alloc` CPU core actually builds
+/// without `std`: only compiled in when the `std` feature is off, so a
+/// `no_std` regression here fails `cargo build --no-default-features`
+/// rather than staying silently unexercised. Not wired up as a `#[test]`,
+/// since the default `cargo test` harness needs `std` itself — that's the
+/// `std` feature's job to provide.
+#[cfg(not(feature = "std"))]
+#[allow(dead_code)]
+mod no_std_build_check {
+    use crate::asm::run_asm;
+    use crate::cpu::CPU;
+    use crate::mem::FlatMemory;
+
+    fn _builds_without_std() {
+        let mut cpu = CPU::new(FlatMemory::new());
+        cpu.power_on();
+        run_asm(&[]);
+    }
+}
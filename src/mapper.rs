@@ -0,0 +1,78 @@
+//! Extension point for cartridge mappers that need more than NROM's fixed
+//! PRG/CHR banks wired straight into [`crate::bus::Bus`]. No bank-switching
+//! mapper is implemented yet — [`crate::rom::Rom`] rejects anything but
+//! mapper 0 — but scanline-counting mappers (MMC3 and its relatives) also
+//! need to drive the CPU's IRQ line, so that half of the trait lands ahead
+//! of one to give [`Bus`](crate::bus::Bus) somewhere to poll from.
+pub trait Mapper {
+    /// Whether the mapper currently wants to assert the CPU's IRQ line
+    /// (e.g. MMC3's scanline counter reaching zero). Defaults to never,
+    /// which is correct for every mapper without an IRQ of its own.
+    fn irq_pending(&self) -> bool {
+        false
+    }
+
+    /// Acknowledges the mapper's IRQ, as a game's interrupt handler would by
+    /// writing the mapper's IRQ-acknowledge register (MMC3's `$E000`).
+    /// Defaults to a no-op, matching [`Self::irq_pending`]'s default of
+    /// never asserting one.
+    fn irq_clear(&mut self) {}
+
+    /// Whether a CPU write to this mapper's ROM-space bank-select register
+    /// exhibits a bus conflict: the NES's open-drain bus ANDs the written
+    /// byte with whatever the ROM chip is simultaneously driving at that
+    /// address, rather than the CPU's byte reaching the register
+    /// unmodified. True for mappers that latch a bank straight off a write
+    /// to ROM space (UxROM, CNROM, and others); false (the default) for
+    /// NROM and any mapper with no writable registers at all, since the
+    /// conflict can't arise without one.
+    fn has_bus_conflicts(&self) -> bool {
+        false
+    }
+
+    /// Notifies the mapper of a CPU write into ROM space (`$8000-$FFFF`),
+    /// carrying the *effective* byte that reached the register — already
+    /// ANDed against the ROM byte at `addr` if [`Self::has_bus_conflicts`]
+    /// says this mapper's bus has that conflict. Defaults to a no-op,
+    /// matching NROM, which has no bank-select register to latch a write
+    /// into.
+    fn notify_prg_write(&mut self, addr: u16, value: u8) {
+        let _ = (addr, value);
+    }
+
+    /// Notifies the mapper of a PPU memory access, for mappers that clock an
+    /// internal scanline counter off the PPU address bus's A12 line
+    /// toggling during background/sprite pattern fetches (MMC3's IRQ
+    /// counter works this way). Not called by anything yet:
+    /// [`crate::ppu::NesPpu::render`] composes a frame in one shot rather
+    /// than modeling per-dot pattern-table fetches, so there's no real A12
+    /// activity to observe. Defaults to a no-op; a scanline-counting mapper
+    /// has a documented hook to implement against once that lands.
+    fn notify_ppu_read(&mut self, addr: u16) {
+        let _ = addr;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct NoIrqMapper;
+    impl Mapper for NoIrqMapper {}
+
+    #[test]
+    fn test_default_mapper_never_asserts_irq() {
+        let mut mapper = NoIrqMapper;
+        assert!(!mapper.irq_pending());
+        mapper.notify_ppu_read(0x1000);
+        mapper.irq_clear();
+        assert!(!mapper.irq_pending());
+    }
+
+    #[test]
+    fn test_default_mapper_has_no_bus_conflicts_and_ignores_prg_writes() {
+        let mut mapper = NoIrqMapper;
+        assert!(!mapper.has_bus_conflicts());
+        mapper.notify_prg_write(0x8000, 0xff); // should not panic
+    }
+}
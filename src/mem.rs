@@ -0,0 +1,213 @@
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+/// Generic addressable memory boundary.
+///
+/// `CPU` is generic over `Mem` so it can be driven either by the simple
+/// [`FlatMemory`] harness used in unit tests, or by a full [`crate::bus::Bus`]
+/// that fans reads/writes out to RAM, the PPU and the cartridge.
+pub trait Mem {
+    fn mem_read(&mut self, addr: u16) -> u8;
+    fn mem_write(&mut self, addr: u16, data: u8);
+
+    fn mem_read_u16(&mut self, pos: u16) -> u16 {
+        let lo = self.mem_read(pos) as u16;
+        let hi = self.mem_read(pos.wrapping_add(1)) as u16;
+        (hi << 8) | lo
+    }
+
+    fn mem_write_u16(&mut self, pos: u16, data: u16) {
+        let hi = (data >> 8) as u8;
+        let lo = (data & 0xff) as u8;
+        self.mem_write(pos, lo);
+        self.mem_write(pos.wrapping_add(1), hi);
+    }
+
+    /// Like [`Self::mem_read_u16`], but for a zero-page pointer: the high
+    /// byte wraps *within* the zero page instead of crossing into page one,
+    /// matching the real 6502's indirect addressing modes (a pointer at
+    /// `0xFF` reads its high byte from `0x00`, not `0x100`).
+    fn mem_read_u16_zp(&mut self, ptr: u8) -> u16 {
+        let lo = self.mem_read(ptr as u16) as u16;
+        let hi = self.mem_read(ptr.wrapping_add(1) as u16) as u16;
+        (hi << 8) | lo
+    }
+
+    /// Reads a little-endian 16-bit value at `pos`, with the same address
+    /// decoding as [`Self::mem_read`]. An alias for [`Self::mem_read_u16`]
+    /// with a name that reads better at call sites outside instruction
+    /// decoding proper — tools reading vectors and pointers (a debugger
+    /// dumping `$FFFC`, say) rather than an addressing mode fetching an
+    /// operand.
+    fn read16(&mut self, pos: u16) -> u16 {
+        self.mem_read_u16(pos)
+    }
+
+    /// Writes a little-endian 16-bit value at `pos`. See [`Self::read16`].
+    fn write16(&mut self, pos: u16, data: u16) {
+        self.mem_write_u16(pos, data)
+    }
+
+    /// Like [`Self::read16`], but for a zero-page pointer. See
+    /// [`Self::mem_read_u16_zp`].
+    fn read16_zp(&mut self, ptr: u8) -> u16 {
+        self.mem_read_u16_zp(ptr)
+    }
+
+    /// Formats `len` bytes starting at `start` as a classic hex dump: 16
+    /// bytes per line, the line's starting address, hex bytes, and an ASCII
+    /// gutter (`.` for non-printable bytes), e.g. `0200: A9 05 85 00 ... |....|`.
+    /// Reads go through [`Self::mem_read`], so mirroring and other
+    /// side-effecting address decoding apply exactly as a real access would.
+    fn hexdump(&mut self, start: u16, len: usize) -> String {
+        let mut lines = Vec::new();
+        let mut offset = 0usize;
+        while offset < len {
+            let addr = start.wrapping_add(offset as u16);
+            let chunk_len = (len - offset).min(16);
+            let bytes: Vec<u8> = (0..chunk_len)
+                .map(|i| self.mem_read(addr.wrapping_add(i as u16)))
+                .collect();
+
+            let hex = bytes
+                .iter()
+                .map(|b| format!("{b:02X}"))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let ascii: String = bytes
+                .iter()
+                .map(|&b| {
+                    if (0x20..=0x7e).contains(&b) {
+                        b as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+
+            lines.push(format!("{addr:04X}: {hex:<47} |{ascii}|"));
+            offset += chunk_len;
+        }
+        lines.join("\n")
+    }
+}
+
+/// A full 64KB byte array — the entire range a 16-bit address can reach.
+/// Wrapping it in a newtype (rather than each `Mem` impl embedding its own
+/// bare `[u8; N]`) keeps the size defined in exactly one place, so a typo'd
+/// array size can't quietly shrink part of the address space out from under
+/// an `addr as usize` index the way `[u8; 0xFFFF]` once did here.
+#[derive(Clone)]
+pub struct Memory([u8; 0x10000]);
+
+impl Memory {
+    pub fn new() -> Self {
+        Memory([0; 0x10000])
+    }
+
+    #[inline]
+    pub fn read(&self, addr: u16) -> u8 {
+        self[addr]
+    }
+
+    #[inline]
+    pub fn write(&mut self, addr: u16, data: u8) {
+        self[addr] = data;
+    }
+}
+
+impl Default for Memory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl core::ops::Index<u16> for Memory {
+    type Output = u8;
+
+    #[inline]
+    fn index(&self, addr: u16) -> &u8 {
+        &self.0[addr as usize]
+    }
+}
+
+impl core::ops::IndexMut<u16> for Memory {
+    #[inline]
+    fn index_mut(&mut self, addr: u16) -> &mut u8 {
+        &mut self.0[addr as usize]
+    }
+}
+
+/// A flat 64KB address space with no mirroring or device mapping.
+///
+/// Used by CPU tests that don't care about the real NES memory map.
+#[derive(Clone)]
+pub struct FlatMemory {
+    data: Memory,
+}
+
+impl FlatMemory {
+    pub fn new() -> Self {
+        FlatMemory {
+            data: Memory::new(),
+        }
+    }
+}
+
+impl Default for FlatMemory {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Mem for FlatMemory {
+    #[inline]
+    fn mem_read(&mut self, addr: u16) -> u8 {
+        self.data.read(addr)
+    }
+
+    #[inline]
+    fn mem_write(&mut self, addr: u16, data: u8) {
+        self.data.write(addr, data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_hexdump_formats_a_known_region_with_address_hex_and_ascii_gutter() {
+        let mut mem = FlatMemory::new();
+        for (i, byte) in (b'A'..=b'Z').take(26).enumerate() {
+            mem.mem_write(0x0200 + i as u16, byte);
+        }
+        mem.mem_write(0x0200 + 26, 0x00); // non-printable, shows as '.'
+
+        let dump = mem.hexdump(0x0200, 32);
+        let lines: Vec<&str> = dump.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            "0200: 41 42 43 44 45 46 47 48 49 4A 4B 4C 4D 4E 4F 50 |ABCDEFGHIJKLMNOP|"
+        );
+        assert_eq!(
+            lines[1],
+            "0210: 51 52 53 54 55 56 57 58 59 5A 00 00 00 00 00 00 |QRSTUVWXYZ......|"
+        );
+    }
+
+    #[test]
+    fn test_memory_reads_and_writes_the_full_address_range_without_panicking() {
+        let mut mem = Memory::new();
+
+        mem.write(0x0000, 0x11);
+        mem.write(0x7fff, 0x22);
+        mem.write(0xffff, 0x33);
+
+        assert_eq!(mem.read(0x0000), 0x11);
+        assert_eq!(mem.read(0x7fff), 0x22);
+        assert_eq!(mem.read(0xffff), 0x33);
+    }
+}
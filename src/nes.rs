@@ -0,0 +1,603 @@
+use std::fmt;
+use std::fs;
+use std::io;
+
+use crate::bus::Bus;
+use crate::cpu::{CpuFlags, Region, CPU};
+use crate::joypad::JoypadButton;
+use crate::ppu::{Frame, PpuRegisterSnapshot};
+use crate::rom::{Rom, RomError};
+use crate::state::{CpuState, PpuState, StateError, SystemState};
+
+/// Errors that can prevent a [`Nes`] from being built.
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    Rom(RomError),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "failed to read ROM file: {err}"),
+            Error::Rom(err) => write!(f, "failed to parse ROM: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+impl From<RomError> for Error {
+    fn from(err: RomError) -> Self {
+        Error::Rom(err)
+    }
+}
+
+/// Top-level handle tying the CPU, bus, PPU and cartridge together into a
+/// runnable system. APU and a second joypad land in later revisions.
+pub struct Nes {
+    cpu: CPU<Bus>,
+    frame: Frame,
+    /// Number of frames advanced by [`Self::step_frame`] or
+    /// [`Self::step_frame_headless`], for callers that need to notice a
+    /// frame boundary without diffing the framebuffer.
+    frame_count: u64,
+}
+
+impl Nes {
+    /// Reads an iNES file from `path` and wires up a ready-to-run NTSC
+    /// system. Use [`Self::from_file_with_region`] for a PAL game dump.
+    pub fn from_file(path: &str) -> Result<Nes, Error> {
+        Self::from_file_with_region(path, Region::default())
+    }
+
+    /// Like [`Self::from_file`], but configures the CPU for `region`'s
+    /// timing instead of always defaulting to NTSC.
+    pub fn from_file_with_region(path: &str, region: Region) -> Result<Nes, Error> {
+        let raw = fs::read(path)?;
+        let rom: Rom = raw.try_into()?;
+        let mut cpu = CPU::new(Bus::new(rom)).with_region(region);
+        cpu.mem.ppu().set_region(region);
+        cpu.power_on();
+        Ok(Nes {
+            cpu,
+            frame: Frame::new(),
+            frame_count: 0,
+        })
+    }
+
+    /// Runs roughly one frame's worth of CPU cycles (per the CPU's
+    /// configured [`Region`]), servicing the PPU's NMI line between
+    /// instructions, then composes the resulting background into
+    /// [`Self::frame`]. Returns the cycles actually run.
+    pub fn step_frame(&mut self) -> u64 {
+        let cycles = self.run_frame_timing();
+        self.frame = self.cpu.mem.ppu().render();
+        cycles
+    }
+
+    /// Like [`Self::step_frame`], but skips composing the framebuffer (and,
+    /// once one exists, audio sample output). CPU and PPU timing — and
+    /// therefore game logic and timers driven off them — advance exactly as
+    /// in [`Self::step_frame`]; only the pixel/audio output work is
+    /// skipped. Meant for fast-forward and save-state scrubbing, where
+    /// intermediate frames are never shown. [`Self::frame`] keeps returning
+    /// whatever was last composed.
+    pub fn step_frame_headless(&mut self) -> u64 {
+        self.run_frame_timing()
+    }
+
+    /// Runs one CPU instruction, ticks the bus (and therefore the PPU, see
+    /// [`crate::bus::Bus::tick`]) by exactly the cycles it took, and
+    /// services any interrupt the PPU or mapper is now asserting. Shared by
+    /// every CPU/PPU timing loop ([`Self::run_frame_timing`],
+    /// [`Self::step_scanline`]) so they all keep the CPU and PPU in
+    /// lockstep the same way. Returns `false` on `BRK`/a `KIL` jam, same as
+    /// [`CPU::step`].
+    fn step_ticked(&mut self) -> bool {
+        let cycles_before = self.cpu.cycles;
+        if !self.cpu.step() {
+            return false;
+        }
+        self.cpu.mem.tick(self.cpu.cycles - cycles_before);
+        if self.cpu.mem.ppu().poll_nmi_interrupt() {
+            self.cpu.request_nmi();
+        }
+        // No APU exists yet to also poll here; a registered mapper
+        // (e.g. a future MMC3) is the only IRQ source today.
+        if self.cpu.mem.mapper_irq_pending() {
+            self.cpu.request_irq();
+        }
+        true
+    }
+
+    /// Runs the CPU until the PPU's raster position moves onto a new
+    /// scanline, for inspecting system state line-by-line (e.g. after a
+    /// sprite-0 hit) instead of only frame-by-frame via [`Self::step_frame`].
+    /// Roughly 113-114 CPU cycles per call, depending where in the current
+    /// scanline's dots the call starts. Crossing into vblank (scanline 241)
+    /// stops right at that boundary like any other scanline — it's not a
+    /// special case here. Returns the cycles actually run.
+    pub fn step_scanline(&mut self) -> u64 {
+        let start_cycles = self.cpu.cycles;
+        let (start_scanline, _) = self.cpu.mem.ppu().ppu_position();
+        while self.step_ticked() {
+            let (scanline, _) = self.cpu.mem.ppu().ppu_position();
+            if scanline != start_scanline {
+                break;
+            }
+        }
+        self.cpu.cycles - start_cycles
+    }
+
+    /// Runs the CPU until the PPU enters vblank (scanline 241), for a
+    /// simple frontend loop: run until vblank, read input, render, repeat.
+    /// Composed from the same [`Self::step_ticked`] machinery as
+    /// [`Self::step_scanline`] and [`Self::run_frame_timing`] rather than
+    /// its own timing logic.
+    ///
+    /// Stops on the scanline *transition* onto 241 rather than polling for
+    /// an exact `(241, 1)` position: a single [`Self::step_ticked`] call can
+    /// tick the PPU by a whole instruction's worth of dots at once (see
+    /// [`crate::bus::Bus::tick`]), so the raster position checked between
+    /// instructions can land anywhere inside scanline 241 — or skip past
+    /// dot 1 of it entirely — without ever landing on that exact dot. If
+    /// the CPU is already sitting on scanline 241 when called, it runs a
+    /// full frame around to the next entry into it rather than returning
+    /// immediately.
+    pub fn run_until_vblank(&mut self) {
+        let mut last_scanline = self.cpu.mem.ppu().ppu_position().0;
+        while self.step_ticked() {
+            let (scanline, _) = self.cpu.mem.ppu().ppu_position();
+            if scanline == 241 && last_scanline != 241 {
+                break;
+            }
+            last_scanline = scanline;
+        }
+    }
+
+    /// Shared CPU/PPU timing loop behind [`Self::step_frame`] and
+    /// [`Self::step_frame_headless`]: advances the CPU roughly one frame's
+    /// worth of cycles, servicing the PPU's NMI line between instructions,
+    /// and counts the frame. Returns the cycles actually run.
+    fn run_frame_timing(&mut self) -> u64 {
+        let start_cycles = self.cpu.cycles;
+        while self.cpu.cycles - start_cycles < self.cpu.cycles_per_frame() {
+            if !self.step_ticked() {
+                break;
+            }
+        }
+        self.frame_count += 1;
+        self.cpu.cycles - start_cycles
+    }
+
+    /// The most recently rendered framebuffer.
+    pub fn frame(&self) -> &Frame {
+        &self.frame
+    }
+
+    /// Number of frames advanced so far by [`Self::step_frame`] or
+    /// [`Self::step_frame_headless`].
+    pub fn frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn set_button(&mut self, button: JoypadButton, pressed: bool) {
+        self.cpu
+            .mem
+            .joypad1()
+            .set_button_pressed_status(button, pressed);
+    }
+
+    /// Emulates pressing the console's physical reset button, as distinct
+    /// from power-on (building a fresh [`Nes`] via [`Self::from_file`]).
+    /// Real hardware reruns the CPU's reset sequence (see [`CPU::reset`])
+    /// but otherwise leaves the system running: the PPU's internal
+    /// registers and VRAM are untouched, and only the APU is silenced and
+    /// has its frame counter reset. Some games behave differently on reset
+    /// than on power-on (skipping the title screen, keeping high scores,
+    /// ...), so the distinction matters.
+    ///
+    /// The APU isn't wired onto the bus yet (see [`crate::apu`]), so
+    /// there's nothing there to silence today; this will reset it too once
+    /// that lands.
+    pub fn reset_button(&mut self) {
+        self.cpu.reset();
+    }
+
+    /// Captures a [`SystemState`] snapshot of the CPU, RAM, PPU and
+    /// controller — everything this emulator models. See the [`crate::state`]
+    /// module docs for what's deliberately left out (APU, mapper registers,
+    /// battery RAM) and why. Safe to call any time, but cleanest right after
+    /// [`Self::step_frame`]/[`Self::step_frame_headless`], at a frame
+    /// boundary, since that's the only point [`Self::frame_count`] reflects
+    /// a whole number of completed frames.
+    pub fn save_state(&mut self) -> SystemState {
+        let cpu_state = CpuState {
+            register_a: self.cpu.register_a,
+            register_x: self.cpu.register_x,
+            register_y: self.cpu.register_y,
+            status: self.cpu.status.bits(),
+            program_counter: self.cpu.program_counter,
+            stack_pointer: self.cpu.stack_pointer,
+            cycles: self.cpu.cycles,
+            region: self.cpu.region(),
+            interrupts: self.cpu.interrupt_snapshot(),
+        };
+
+        let ram = *self.cpu.mem.ram();
+        let ppu = self.cpu.mem.ppu();
+        let reg = ppu.register_snapshot();
+        let ppu_state = PpuState {
+            chr_rom: ppu.chr_rom.clone(),
+            vram: ppu.vram.clone(),
+            oam_data: ppu.oam_data,
+            palette_table: ppu.palette_table,
+            ctrl_bits: reg.ctrl_bits,
+            status_bits: reg.status_bits,
+            addr: reg.addr,
+            internal_data_buf: reg.internal_data_buf,
+            vblank: reg.vblank,
+            nmi_line: reg.nmi_line,
+            rendering_active: reg.rendering_active,
+            io_latch: reg.io_latch,
+            scroll_x: reg.scroll_x,
+            scroll_y: reg.scroll_y,
+            write_toggle: reg.write_toggle,
+            scanline: reg.scanline,
+            dot: reg.dot,
+            scanlines_per_frame: reg.scanlines_per_frame,
+        };
+
+        let joypad1 = self.cpu.mem.joypad1().shift_register_snapshot();
+
+        SystemState::new(cpu_state, ram, ppu_state, joypad1, self.frame_count)
+    }
+
+    /// Restores a [`SystemState`] captured by [`Self::save_state`] — on this
+    /// `Nes` or another instance loaded from the same ROM. Re-renders
+    /// [`Self::frame`] from the restored PPU state before returning, so the
+    /// framebuffer is correct immediately without needing another
+    /// [`Self::step_frame`].
+    pub fn load_state(&mut self, state: SystemState) -> Result<(), StateError> {
+        if state.ppu.chr_rom.len() != self.cpu.mem.ppu().chr_rom.len() {
+            return Err(StateError::ChrSizeMismatch);
+        }
+
+        self.cpu.register_a = state.cpu.register_a;
+        self.cpu.register_x = state.cpu.register_x;
+        self.cpu.register_y = state.cpu.register_y;
+        self.cpu.status = CpuFlags::from_bits_truncate(state.cpu.status);
+        self.cpu.program_counter = state.cpu.program_counter;
+        self.cpu.stack_pointer = state.cpu.stack_pointer;
+        self.cpu.cycles = state.cpu.cycles;
+        self.cpu.set_region(state.cpu.region);
+        self.cpu.restore_interrupt_snapshot(state.cpu.interrupts);
+
+        self.cpu.mem.set_ram(state.ram);
+        let ppu = self.cpu.mem.ppu();
+        ppu.chr_rom = state.ppu.chr_rom;
+        ppu.vram = state.ppu.vram;
+        ppu.oam_data = state.ppu.oam_data;
+        ppu.palette_table = state.ppu.palette_table;
+        ppu.set_region(state.cpu.region);
+        ppu.restore_register_snapshot(PpuRegisterSnapshot {
+            ctrl_bits: state.ppu.ctrl_bits,
+            status_bits: state.ppu.status_bits,
+            addr: state.ppu.addr,
+            internal_data_buf: state.ppu.internal_data_buf,
+            vblank: state.ppu.vblank,
+            nmi_line: state.ppu.nmi_line,
+            rendering_active: state.ppu.rendering_active,
+            io_latch: state.ppu.io_latch,
+            scroll_x: state.ppu.scroll_x,
+            scroll_y: state.ppu.scroll_y,
+            write_toggle: state.ppu.write_toggle,
+            scanline: state.ppu.scanline,
+            dot: state.ppu.dot,
+            scanlines_per_frame: state.ppu.scanlines_per_frame,
+        });
+
+        self.cpu
+            .mem
+            .joypad1()
+            .restore_shift_register_snapshot(state.joypad1);
+
+        self.frame_count = state.frame_count;
+        self.frame = self.cpu.mem.ppu().render();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::mem::Mem;
+    use std::env;
+    use std::fs;
+
+    fn write_tiny_rom(path: &std::path::Path) {
+        let mut raw = vec![0x4e, 0x45, 0x53, 0x1a, 1, 1, 0, 0];
+        raw.extend(std::iter::repeat_n(0, 8)); // rest of the 16-byte header
+        raw.extend(std::iter::repeat_n(0, 0x4000)); // PRG ROM, all BRK
+        raw.extend(std::iter::repeat_n(0, 0x2000)); // CHR ROM
+        fs::write(path, raw).unwrap();
+    }
+
+    #[test]
+    fn test_from_file_steps_a_frame_without_panicking() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth114_test_rom.nes");
+        write_tiny_rom(&path);
+
+        let mut nes = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+        nes.step_frame();
+        nes.set_button(JoypadButton::BUTTON_A, true);
+        assert_eq!(nes.frame().pixels.len(), Frame::WIDTH * Frame::HEIGHT * 3);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_from_file_with_region_pal_wires_the_longer_frame_into_the_ppu() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth131_test_rom.nes");
+        write_nop_loop_rom(&path);
+
+        let mut nes = Nes::from_file_with_region(path.to_str().unwrap(), Region::Pal)
+            .expect("tiny ROM should parse");
+
+        // Walk scanline-by-scanline past NTSC's wraparound point (262) and
+        // confirm the PPU kept going instead of wrapping back to 0 there.
+        for _ in 0..262 {
+            nes.step_scanline();
+        }
+        assert_ne!(nes.cpu.mem.ppu().ppu_position().0, 0);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_save_state_round_trip_matches_a_fresh_run_from_the_save_point() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth162_test_rom.nes");
+        write_tiny_rom(&path);
+
+        let mut original = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+        original.step_frame();
+        // Leave a PPUSCROLL write half-finished (write_toggle now false,
+        // scroll_x latched) and the raster position mid-scanline, so the
+        // save state has to actually round-trip them instead of both
+        // happening to already sit at their post-reset defaults.
+        original.cpu.mem_write(0x2005, 0x11);
+        original.cpu.mem.ppu().tick(100);
+        let saved = original.save_state();
+        let saved_position = original.cpu.mem.ppu().ppu_position();
+
+        // Run the original further from the save point.
+        for _ in 0..3 {
+            original.step_frame();
+        }
+
+        // A second, independently-constructed Nes restored from the saved
+        // state and run the same number of frames should land in exactly
+        // the same place as the original did.
+        let mut restored = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+        restored
+            .load_state(saved)
+            .expect("same ROM should restore cleanly");
+        assert_eq!(restored.cpu.mem.ppu().ppu_position(), saved_position);
+        for _ in 0..3 {
+            restored.step_frame();
+        }
+
+        assert_eq!(restored.frame_count(), original.frame_count());
+        assert_eq!(restored.frame().pixels, original.frame().pixels);
+        assert_eq!(
+            restored.save_state().to_bytes(),
+            original.save_state().to_bytes()
+        );
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_load_state_rejects_a_save_state_from_a_different_chr_size() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth162_mismatch_rom.nes");
+        write_tiny_rom(&path);
+
+        let mut small = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+        let saved = small.save_state();
+
+        let mut big_path = env::temp_dir();
+        big_path.push("nes_emulator_synth162_mismatch_rom_big.nes");
+        let mut raw = vec![0x4e, 0x45, 0x53, 0x1a, 1, 2, 0, 0];
+        raw.extend(std::iter::repeat_n(0, 8));
+        raw.extend(std::iter::repeat_n(0, 0x4000));
+        raw.extend(std::iter::repeat_n(0, 0x4000)); // 2 CHR-ROM banks instead of 1
+        fs::write(&big_path, raw).unwrap();
+
+        let mut big = Nes::from_file(big_path.to_str().unwrap()).expect("ROM should parse");
+        assert_eq!(
+            big.load_state(saved).unwrap_err(),
+            StateError::ChrSizeMismatch
+        );
+
+        fs::remove_file(&path).ok();
+        fs::remove_file(&big_path).ok();
+    }
+
+    #[test]
+    fn test_from_file_wires_chr_rom_into_ppu_pattern_table_reads() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth139_chr_test_rom.nes");
+
+        let mut raw = vec![0x4e, 0x45, 0x53, 0x1a, 1, 1, 0, 0];
+        raw.extend(std::iter::repeat_n(0, 8)); // rest of the 16-byte header
+        raw.extend(std::iter::repeat_n(0, 0x4000)); // PRG ROM, all BRK
+        let mut chr = vec![0u8; 0x2000];
+        chr[0] = 0xab; // recognizable byte at pattern address 0
+        raw.extend(chr);
+        fs::write(&path, raw).unwrap();
+
+        let mut nes = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+
+        nes.cpu.mem_write(0x2006, 0x00); // PPUADDR high byte
+        nes.cpu.mem_write(0x2006, 0x00); // PPUADDR low byte: $0000
+        nes.cpu.mem_read(0x2007); // primes the read buffer from $0000
+        nes.cpu.mem_write(0x2006, 0x00);
+        nes.cpu.mem_write(0x2006, 0x00);
+        assert_eq!(nes.cpu.mem_read(0x2007), 0xab);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_step_frame_headless_matches_timing_but_skips_the_framebuffer() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth155_test_rom.nes");
+        write_tiny_rom(&path);
+
+        let mut normal = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+        let mut headless = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+
+        let normal_cycles = normal.step_frame();
+        let headless_cycles = headless.step_frame_headless();
+
+        assert_eq!(normal_cycles, headless_cycles);
+        assert_eq!(normal.frame_count(), headless.frame_count());
+
+        // step_frame composed a background (the universal background color
+        // is non-zero gray), while step_frame_headless left the framebuffer
+        // at its untouched, all-zero default.
+        assert_ne!(normal.frame().pixels, Frame::new().pixels);
+        assert_eq!(headless.frame().pixels, Frame::new().pixels);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_reset_button_preserves_vram_but_resets_cpu_registers() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth187_test_rom.nes");
+        write_tiny_rom(&path);
+
+        let mut nes = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+        nes.cpu.mem.ppu().set_vram(0x2400, &[0xab]);
+        let vram_index = nes.cpu.mem.ppu().mirror_vram_addr(0x2400) as usize;
+        nes.cpu.register_a = 0x42;
+        nes.cpu.stack_pointer = 0x80;
+
+        nes.reset_button();
+
+        // VRAM (and A/X/Y, which the reset sequence doesn't touch) survive
+        // a reset the way they would power-on.
+        assert_eq!(nes.cpu.mem.ppu().vram[vram_index], 0xab);
+        assert_eq!(nes.cpu.register_a, 0x42);
+        // The reset sequence itself ran: three dummy stack pushes and the
+        // interrupt-disable flag forced on, same as CPU::reset.
+        assert_eq!(nes.cpu.stack_pointer, 0x7d);
+        assert!(nes.cpu.flag(CpuFlags::INTERRUPT_DISABLE));
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_from_file_rejects_non_ines_file() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth114_garbage.nes");
+        fs::write(&path, vec![0u8; 32]).unwrap();
+
+        assert!(Nes::from_file(path.to_str().unwrap()).is_err());
+
+        fs::remove_file(&path).ok();
+    }
+
+    fn write_nop_loop_rom(path: &std::path::Path) {
+        let mut raw = vec![0x4e, 0x45, 0x53, 0x1a, 1, 1, 0, 0];
+        raw.extend(std::iter::repeat_n(0, 8)); // rest of the 16-byte header
+        let mut prg = vec![0xea; 0x4000]; // NOP forever, never hits BRK
+        prg[0x3ffc] = 0x00; // reset vector -> $8000
+        prg[0x3ffd] = 0x80;
+        raw.extend(prg);
+        raw.extend(std::iter::repeat_n(0, 0x2000)); // CHR ROM
+        fs::write(path, raw).unwrap();
+    }
+
+    #[test]
+    fn test_step_scanline_advances_the_ppu_scanline_by_exactly_one_each_call() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth196_test_rom.nes");
+        write_nop_loop_rom(&path);
+
+        let mut nes = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+
+        let mut last_scanline = nes.cpu.mem.ppu().ppu_position().0;
+        for _ in 0..10 {
+            nes.step_scanline();
+            let (scanline, _) = nes.cpu.mem.ppu().ppu_position();
+            assert_eq!(scanline, (last_scanline + 1) % 262);
+            last_scanline = scanline;
+        }
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_run_until_vblank_stops_at_scanline_241_dot_1() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth199_test_rom.nes");
+        write_nop_loop_rom(&path);
+
+        let mut nes = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+
+        nes.run_until_vblank();
+
+        assert_eq!(nes.cpu.mem.ppu().ppu_position(), (241, 1));
+
+        fs::remove_file(&path).ok();
+    }
+
+    /// A loop whose iteration length in dots (14 NOPs + a `JMP` back to the
+    /// top, 31 cycles = 93 dots) was picked so that none of its
+    /// instruction-boundary checkpoints land exactly on dot 1 of any
+    /// scanline, including 241 — a regression fixture for
+    /// [`Nes::run_until_vblank`], which used to poll for that exact
+    /// position instead of the scanline-241 transition.
+    fn write_odd_cycle_loop_rom(path: &std::path::Path) {
+        let mut raw = vec![0x4e, 0x45, 0x53, 0x1a, 1, 1, 0, 0];
+        raw.extend(std::iter::repeat_n(0, 8)); // rest of the 16-byte header
+        let mut prg = vec![0; 0x4000];
+        prg[0..14].fill(0xea); // 14 NOPs
+        prg[14] = 0x4c; // JMP $8000
+        prg[15] = 0x00;
+        prg[16] = 0x80;
+        prg[0x3ffc] = 0x00; // reset vector -> $8000
+        prg[0x3ffd] = 0x80;
+        raw.extend(prg);
+        raw.extend(std::iter::repeat_n(0, 0x2000)); // CHR ROM
+        fs::write(path, raw).unwrap();
+    }
+
+    #[test]
+    fn test_run_until_vblank_terminates_even_when_no_instruction_boundary_lands_on_dot_1() {
+        let mut path = env::temp_dir();
+        path.push("nes_emulator_synth199_test_rom_odd_cycle.nes");
+        write_odd_cycle_loop_rom(&path);
+
+        let mut nes = Nes::from_file(path.to_str().unwrap()).expect("tiny ROM should parse");
+
+        nes.run_until_vblank();
+
+        assert_eq!(nes.cpu.mem.ppu().ppu_position().0, 241);
+
+        fs::remove_file(&path).ok();
+    }
+}
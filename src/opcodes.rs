@@ -0,0 +1,667 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[allow(non_camel_case_types)]
+pub enum AddressingMode {
+    Immediate,
+    ZeroPage,
+    ZeroPage_X,
+    ZeroPage_Y,
+    Absolute,
+    Absolute_X,
+    Absolute_Y,
+    Indirect_X,
+    Indirect_Y,
+    Accumulator,
+    NoneAddressing,
+}
+
+/// The 56 official 6502 mnemonics, as a type-checked alternative to
+/// matching on [`OpCode::mnemonic`]'s raw string. Get one from an `OpCode`
+/// via [`OpCode::mnemonic_enum`], or straight from a raw opcode byte via
+/// `TryFrom<u8>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mnemonic {
+    ADC,
+    AND,
+    ASL,
+    BCC,
+    BCS,
+    BEQ,
+    BIT,
+    BMI,
+    BNE,
+    BPL,
+    BRK,
+    BVC,
+    BVS,
+    CLC,
+    CLD,
+    CLI,
+    CLV,
+    CMP,
+    CPX,
+    CPY,
+    DEC,
+    DEX,
+    DEY,
+    EOR,
+    INC,
+    INX,
+    INY,
+    JMP,
+    JSR,
+    LDA,
+    LDX,
+    LDY,
+    LSR,
+    NOP,
+    ORA,
+    PHA,
+    PHP,
+    PLA,
+    PLP,
+    ROL,
+    ROR,
+    RTI,
+    RTS,
+    SBC,
+    SEC,
+    SED,
+    SEI,
+    STA,
+    STX,
+    STY,
+    TAX,
+    TAY,
+    TSX,
+    TXA,
+    TXS,
+    TYA,
+}
+
+/// A raw byte that isn't any official 6502 opcode, returned by
+/// `Mnemonic::try_from(byte)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnrecognizedOpcode(pub u8);
+
+impl TryFrom<u8> for Mnemonic {
+    type Error = UnrecognizedOpcode;
+
+    fn try_from(byte: u8) -> Result<Mnemonic, UnrecognizedOpcode> {
+        OPCODES_TABLE[byte as usize]
+            .and_then(OpCode::mnemonic_enum_checked)
+            .ok_or(UnrecognizedOpcode(byte))
+    }
+}
+
+pub struct OpCode {
+    pub code: u8,
+    pub mnemonic: &'static str,
+    pub len: u8,
+    pub cycles: u8,
+    pub mode: AddressingMode,
+    /// Whether crossing a page boundary while resolving the operand address
+    /// costs an extra cycle. True for indexed reads (`LDA abs,X` and
+    /// friends); false for stores and read-modify-write instructions, which
+    /// always take their listed `cycles` regardless of crossing.
+    pub page_cross_penalty: bool,
+}
+
+impl OpCode {
+    const fn new(
+        code: u8,
+        mnemonic: &'static str,
+        len: u8,
+        cycles: u8,
+        mode: AddressingMode,
+    ) -> Self {
+        OpCode {
+            code,
+            mnemonic,
+            len,
+            cycles,
+            mode,
+            page_cross_penalty: false,
+        }
+    }
+
+    /// Marks this opcode as taking the conditional +1-cycle page-cross
+    /// penalty. Chains off [`Self::new`] at each indexed-read call site.
+    const fn with_page_cross_penalty(mut self) -> Self {
+        self.page_cross_penalty = true;
+        self
+    }
+
+    /// The type-checked [`Mnemonic`] counterpart to [`Self::mnemonic`]'s raw
+    /// string. Panics if `mnemonic` isn't one of the 56 official mnemonics —
+    /// that can't happen for an official `OpCode` from [`CPU_OPS_CODES`],
+    /// but does for undocumented ones like `"KIL"`; use
+    /// [`Self::mnemonic_enum_checked`] if `self` might be one of those.
+    pub fn mnemonic_enum(&self) -> Mnemonic {
+        self.mnemonic_enum_checked().unwrap_or_else(|| {
+            panic!(
+                "{} is not one of the 56 official 6502 mnemonics",
+                self.mnemonic
+            )
+        })
+    }
+
+    /// Like [`Self::mnemonic_enum`], but returns `None` instead of panicking
+    /// for an undocumented mnemonic (e.g. `"KIL"`) that has no [`Mnemonic`]
+    /// variant of its own.
+    pub fn mnemonic_enum_checked(&self) -> Option<Mnemonic> {
+        Some(match self.mnemonic {
+            "ADC" => Mnemonic::ADC,
+            "AND" => Mnemonic::AND,
+            "ASL" => Mnemonic::ASL,
+            "BCC" => Mnemonic::BCC,
+            "BCS" => Mnemonic::BCS,
+            "BEQ" => Mnemonic::BEQ,
+            "BIT" => Mnemonic::BIT,
+            "BMI" => Mnemonic::BMI,
+            "BNE" => Mnemonic::BNE,
+            "BPL" => Mnemonic::BPL,
+            "BRK" => Mnemonic::BRK,
+            "BVC" => Mnemonic::BVC,
+            "BVS" => Mnemonic::BVS,
+            "CLC" => Mnemonic::CLC,
+            "CLD" => Mnemonic::CLD,
+            "CLI" => Mnemonic::CLI,
+            "CLV" => Mnemonic::CLV,
+            "CMP" => Mnemonic::CMP,
+            "CPX" => Mnemonic::CPX,
+            "CPY" => Mnemonic::CPY,
+            "DEC" => Mnemonic::DEC,
+            "DEX" => Mnemonic::DEX,
+            "DEY" => Mnemonic::DEY,
+            "EOR" => Mnemonic::EOR,
+            "INC" => Mnemonic::INC,
+            "INX" => Mnemonic::INX,
+            "INY" => Mnemonic::INY,
+            "JMP" => Mnemonic::JMP,
+            "JSR" => Mnemonic::JSR,
+            "LDA" => Mnemonic::LDA,
+            "LDX" => Mnemonic::LDX,
+            "LDY" => Mnemonic::LDY,
+            "LSR" => Mnemonic::LSR,
+            "NOP" => Mnemonic::NOP,
+            "ORA" => Mnemonic::ORA,
+            "PHA" => Mnemonic::PHA,
+            "PHP" => Mnemonic::PHP,
+            "PLA" => Mnemonic::PLA,
+            "PLP" => Mnemonic::PLP,
+            "ROL" => Mnemonic::ROL,
+            "ROR" => Mnemonic::ROR,
+            "RTI" => Mnemonic::RTI,
+            "RTS" => Mnemonic::RTS,
+            "SBC" => Mnemonic::SBC,
+            "SEC" => Mnemonic::SEC,
+            "SED" => Mnemonic::SED,
+            "SEI" => Mnemonic::SEI,
+            "STA" => Mnemonic::STA,
+            "STX" => Mnemonic::STX,
+            "STY" => Mnemonic::STY,
+            "TAX" => Mnemonic::TAX,
+            "TAY" => Mnemonic::TAY,
+            "TSX" => Mnemonic::TSX,
+            "TXA" => Mnemonic::TXA,
+            "TXS" => Mnemonic::TXS,
+            "TYA" => Mnemonic::TYA,
+            _ => return None,
+        })
+    }
+}
+
+/// How many operand bytes follow the opcode byte itself, by addressing
+/// mode — a single source of truth for the assembler, disassembler, and
+/// cycle logic, instead of `len - 1` arithmetic scattered across all
+/// three.
+///
+/// `NoneAddressing` always reports 0 here, which holds for every truly
+/// implied opcode, but not for the relative branches or `JMP`/`JSR`: those
+/// encode 1 or 2 operand bytes in [`OpCode::len`] without a distinct
+/// addressing mode of their own, so callers that need their operand width
+/// have to read `len` directly instead of going through this helper.
+pub fn operand_bytes(mode: &AddressingMode) -> u8 {
+    use AddressingMode::*;
+    match mode {
+        NoneAddressing | Accumulator => 0,
+        Immediate | ZeroPage | ZeroPage_X | ZeroPage_Y | Indirect_X | Indirect_Y => 1,
+        Absolute | Absolute_X | Absolute_Y => 2,
+    }
+}
+
+/// Whether `mnemonic` is one of the 8 relative-branch instructions — the
+/// `NoneAddressing` opcodes whose single operand byte is actually a signed
+/// offset from the following instruction, not an implied no-operand opcode.
+/// A single source of truth for [`CPU::step`](crate::cpu::CPU::step)'s
+/// fall-through-length exception and the disassembler's target annotation.
+pub fn is_branch(mnemonic: &str) -> bool {
+    matches!(
+        mnemonic,
+        "BCC" | "BCS" | "BEQ" | "BNE" | "BMI" | "BPL" | "BVC" | "BVS"
+    )
+}
+
+/// The 6502's full opcode table, by mnemonic. A plain `const` array rather
+/// than a lazily-built `Vec` — the data is entirely static, so there's
+/// nothing to gain from deferring construction to first use, and a `const`
+/// needs no runtime synchronization (handy for [`OPCODES_TABLE`], and for
+/// `no_std` targets with no `std::sync::LazyLock`).
+pub const CPU_OPS_CODES: &[OpCode] = {
+    use AddressingMode::*;
+    &[
+        OpCode::new(0x00, "BRK", 1, 7, NoneAddressing),
+        OpCode::new(0xea, "NOP", 1, 2, NoneAddressing),
+        // ADC
+        OpCode::new(0x69, "ADC", 2, 2, Immediate),
+        OpCode::new(0x65, "ADC", 2, 3, ZeroPage),
+        OpCode::new(0x75, "ADC", 2, 4, ZeroPage_X),
+        OpCode::new(0x6d, "ADC", 3, 4, Absolute),
+        OpCode::new(0x7d, "ADC", 3, 4, Absolute_X).with_page_cross_penalty(),
+        OpCode::new(0x79, "ADC", 3, 4, Absolute_Y).with_page_cross_penalty(),
+        OpCode::new(0x61, "ADC", 2, 6, Indirect_X),
+        OpCode::new(0x71, "ADC", 2, 5, Indirect_Y).with_page_cross_penalty(),
+        // SBC
+        OpCode::new(0xe9, "SBC", 2, 2, Immediate),
+        OpCode::new(0xe5, "SBC", 2, 3, ZeroPage),
+        OpCode::new(0xf5, "SBC", 2, 4, ZeroPage_X),
+        OpCode::new(0xed, "SBC", 3, 4, Absolute),
+        OpCode::new(0xfd, "SBC", 3, 4, Absolute_X).with_page_cross_penalty(),
+        OpCode::new(0xf9, "SBC", 3, 4, Absolute_Y).with_page_cross_penalty(),
+        OpCode::new(0xe1, "SBC", 2, 6, Indirect_X),
+        OpCode::new(0xf1, "SBC", 2, 5, Indirect_Y).with_page_cross_penalty(),
+        // AND
+        OpCode::new(0x29, "AND", 2, 2, Immediate),
+        OpCode::new(0x25, "AND", 2, 3, ZeroPage),
+        OpCode::new(0x35, "AND", 2, 4, ZeroPage_X),
+        OpCode::new(0x2d, "AND", 3, 4, Absolute),
+        OpCode::new(0x3d, "AND", 3, 4, Absolute_X).with_page_cross_penalty(),
+        OpCode::new(0x39, "AND", 3, 4, Absolute_Y).with_page_cross_penalty(),
+        OpCode::new(0x21, "AND", 2, 6, Indirect_X),
+        OpCode::new(0x31, "AND", 2, 5, Indirect_Y).with_page_cross_penalty(),
+        // EOR
+        OpCode::new(0x49, "EOR", 2, 2, Immediate),
+        OpCode::new(0x45, "EOR", 2, 3, ZeroPage),
+        OpCode::new(0x55, "EOR", 2, 4, ZeroPage_X),
+        OpCode::new(0x4d, "EOR", 3, 4, Absolute),
+        OpCode::new(0x5d, "EOR", 3, 4, Absolute_X).with_page_cross_penalty(),
+        OpCode::new(0x59, "EOR", 3, 4, Absolute_Y).with_page_cross_penalty(),
+        OpCode::new(0x41, "EOR", 2, 6, Indirect_X),
+        OpCode::new(0x51, "EOR", 2, 5, Indirect_Y).with_page_cross_penalty(),
+        // ORA
+        OpCode::new(0x09, "ORA", 2, 2, Immediate),
+        OpCode::new(0x05, "ORA", 2, 3, ZeroPage),
+        OpCode::new(0x15, "ORA", 2, 4, ZeroPage_X),
+        OpCode::new(0x0d, "ORA", 3, 4, Absolute),
+        OpCode::new(0x1d, "ORA", 3, 4, Absolute_X).with_page_cross_penalty(),
+        OpCode::new(0x19, "ORA", 3, 4, Absolute_Y).with_page_cross_penalty(),
+        OpCode::new(0x01, "ORA", 2, 6, Indirect_X),
+        OpCode::new(0x11, "ORA", 2, 5, Indirect_Y).with_page_cross_penalty(),
+        // Shifts / rotates
+        OpCode::new(0x0a, "ASL", 1, 2, Accumulator),
+        OpCode::new(0x06, "ASL", 2, 5, ZeroPage),
+        OpCode::new(0x16, "ASL", 2, 6, ZeroPage_X),
+        OpCode::new(0x0e, "ASL", 3, 6, Absolute),
+        OpCode::new(0x1e, "ASL", 3, 7, Absolute_X),
+        OpCode::new(0x4a, "LSR", 1, 2, Accumulator),
+        OpCode::new(0x46, "LSR", 2, 5, ZeroPage),
+        OpCode::new(0x56, "LSR", 2, 6, ZeroPage_X),
+        OpCode::new(0x4e, "LSR", 3, 6, Absolute),
+        OpCode::new(0x5e, "LSR", 3, 7, Absolute_X),
+        OpCode::new(0x2a, "ROL", 1, 2, Accumulator),
+        OpCode::new(0x26, "ROL", 2, 5, ZeroPage),
+        OpCode::new(0x36, "ROL", 2, 6, ZeroPage_X),
+        OpCode::new(0x2e, "ROL", 3, 6, Absolute),
+        OpCode::new(0x3e, "ROL", 3, 7, Absolute_X),
+        OpCode::new(0x6a, "ROR", 1, 2, Accumulator),
+        OpCode::new(0x66, "ROR", 2, 5, ZeroPage),
+        OpCode::new(0x76, "ROR", 2, 6, ZeroPage_X),
+        OpCode::new(0x6e, "ROR", 3, 6, Absolute),
+        OpCode::new(0x7e, "ROR", 3, 7, Absolute_X),
+        // INC/DEC
+        OpCode::new(0xe6, "INC", 2, 5, ZeroPage),
+        OpCode::new(0xf6, "INC", 2, 6, ZeroPage_X),
+        OpCode::new(0xee, "INC", 3, 6, Absolute),
+        OpCode::new(0xfe, "INC", 3, 7, Absolute_X),
+        OpCode::new(0xc6, "DEC", 2, 5, ZeroPage),
+        OpCode::new(0xd6, "DEC", 2, 6, ZeroPage_X),
+        OpCode::new(0xce, "DEC", 3, 6, Absolute),
+        OpCode::new(0xde, "DEC", 3, 7, Absolute_X),
+        OpCode::new(0xe8, "INX", 1, 2, NoneAddressing),
+        OpCode::new(0xc8, "INY", 1, 2, NoneAddressing),
+        OpCode::new(0xca, "DEX", 1, 2, NoneAddressing),
+        OpCode::new(0x88, "DEY", 1, 2, NoneAddressing),
+        // CMP/CPX/CPY
+        OpCode::new(0xc9, "CMP", 2, 2, Immediate),
+        OpCode::new(0xc5, "CMP", 2, 3, ZeroPage),
+        OpCode::new(0xd5, "CMP", 2, 4, ZeroPage_X),
+        OpCode::new(0xcd, "CMP", 3, 4, Absolute),
+        OpCode::new(0xdd, "CMP", 3, 4, Absolute_X).with_page_cross_penalty(),
+        OpCode::new(0xd9, "CMP", 3, 4, Absolute_Y).with_page_cross_penalty(),
+        OpCode::new(0xc1, "CMP", 2, 6, Indirect_X),
+        OpCode::new(0xd1, "CMP", 2, 5, Indirect_Y).with_page_cross_penalty(),
+        OpCode::new(0xe0, "CPX", 2, 2, Immediate),
+        OpCode::new(0xe4, "CPX", 2, 3, ZeroPage),
+        OpCode::new(0xec, "CPX", 3, 4, Absolute),
+        OpCode::new(0xc0, "CPY", 2, 2, Immediate),
+        OpCode::new(0xc4, "CPY", 2, 3, ZeroPage),
+        OpCode::new(0xcc, "CPY", 3, 4, Absolute),
+        // Branches
+        OpCode::new(0x90, "BCC", 2, 2, NoneAddressing),
+        OpCode::new(0xb0, "BCS", 2, 2, NoneAddressing),
+        OpCode::new(0xf0, "BEQ", 2, 2, NoneAddressing),
+        OpCode::new(0x30, "BMI", 2, 2, NoneAddressing),
+        OpCode::new(0xd0, "BNE", 2, 2, NoneAddressing),
+        OpCode::new(0x10, "BPL", 2, 2, NoneAddressing),
+        OpCode::new(0x50, "BVC", 2, 2, NoneAddressing),
+        OpCode::new(0x70, "BVS", 2, 2, NoneAddressing),
+        // BIT
+        OpCode::new(0x24, "BIT", 2, 3, ZeroPage),
+        OpCode::new(0x2c, "BIT", 3, 4, Absolute),
+        // Flags
+        OpCode::new(0x18, "CLC", 1, 2, NoneAddressing),
+        OpCode::new(0xd8, "CLD", 1, 2, NoneAddressing),
+        OpCode::new(0x58, "CLI", 1, 2, NoneAddressing),
+        OpCode::new(0xb8, "CLV", 1, 2, NoneAddressing),
+        OpCode::new(0x38, "SEC", 1, 2, NoneAddressing),
+        OpCode::new(0xf8, "SED", 1, 2, NoneAddressing),
+        OpCode::new(0x78, "SEI", 1, 2, NoneAddressing),
+        // Jumps / calls
+        OpCode::new(0x4c, "JMP", 3, 3, NoneAddressing), // absolute
+        OpCode::new(0x6c, "JMP", 3, 5, NoneAddressing), // indirect
+        OpCode::new(0x20, "JSR", 3, 6, NoneAddressing),
+        OpCode::new(0x60, "RTS", 1, 6, NoneAddressing),
+        OpCode::new(0x40, "RTI", 1, 6, NoneAddressing),
+        // Stack
+        OpCode::new(0x48, "PHA", 1, 3, NoneAddressing),
+        OpCode::new(0x68, "PLA", 1, 4, NoneAddressing),
+        OpCode::new(0x08, "PHP", 1, 3, NoneAddressing),
+        OpCode::new(0x28, "PLP", 1, 4, NoneAddressing),
+        // Loads
+        OpCode::new(0xa9, "LDA", 2, 2, Immediate),
+        OpCode::new(0xa5, "LDA", 2, 3, ZeroPage),
+        OpCode::new(0xb5, "LDA", 2, 4, ZeroPage_X),
+        OpCode::new(0xad, "LDA", 3, 4, Absolute),
+        OpCode::new(0xbd, "LDA", 3, 4, Absolute_X).with_page_cross_penalty(),
+        OpCode::new(0xb9, "LDA", 3, 4, Absolute_Y).with_page_cross_penalty(),
+        OpCode::new(0xa1, "LDA", 2, 6, Indirect_X),
+        OpCode::new(0xb1, "LDA", 2, 5, Indirect_Y).with_page_cross_penalty(),
+        OpCode::new(0xa2, "LDX", 2, 2, Immediate),
+        OpCode::new(0xa6, "LDX", 2, 3, ZeroPage),
+        OpCode::new(0xb6, "LDX", 2, 4, ZeroPage_Y),
+        OpCode::new(0xae, "LDX", 3, 4, Absolute),
+        OpCode::new(0xbe, "LDX", 3, 4, Absolute_Y).with_page_cross_penalty(),
+        OpCode::new(0xa0, "LDY", 2, 2, Immediate),
+        OpCode::new(0xa4, "LDY", 2, 3, ZeroPage),
+        OpCode::new(0xb4, "LDY", 2, 4, ZeroPage_X),
+        OpCode::new(0xac, "LDY", 3, 4, Absolute),
+        OpCode::new(0xbc, "LDY", 3, 4, Absolute_X).with_page_cross_penalty(),
+        // Stores
+        OpCode::new(0x85, "STA", 2, 3, ZeroPage),
+        OpCode::new(0x95, "STA", 2, 4, ZeroPage_X),
+        OpCode::new(0x8d, "STA", 3, 4, Absolute),
+        OpCode::new(0x9d, "STA", 3, 5, Absolute_X),
+        OpCode::new(0x99, "STA", 3, 5, Absolute_Y),
+        OpCode::new(0x81, "STA", 2, 6, Indirect_X),
+        OpCode::new(0x91, "STA", 2, 6, Indirect_Y),
+        OpCode::new(0x86, "STX", 2, 3, ZeroPage),
+        OpCode::new(0x96, "STX", 2, 4, ZeroPage_Y),
+        OpCode::new(0x8e, "STX", 3, 4, Absolute),
+        OpCode::new(0x84, "STY", 2, 3, ZeroPage),
+        OpCode::new(0x94, "STY", 2, 4, ZeroPage_X),
+        OpCode::new(0x8c, "STY", 3, 4, Absolute),
+        // Register transfers
+        OpCode::new(0xaa, "TAX", 1, 2, NoneAddressing),
+        OpCode::new(0xa8, "TAY", 1, 2, NoneAddressing),
+        OpCode::new(0xba, "TSX", 1, 2, NoneAddressing),
+        OpCode::new(0x8a, "TXA", 1, 2, NoneAddressing),
+        OpCode::new(0x9a, "TXS", 1, 2, NoneAddressing),
+        OpCode::new(0x98, "TYA", 1, 2, NoneAddressing),
+        // Undocumented KIL/JAM: locks up the CPU on real hardware instead
+        // of decoding as any official instruction. Some test ROMs execute
+        // one deliberately to signal failure, so it's modeled as a clean
+        // halt (see `CPU::step`'s "KIL" arm) rather than left unrecognized,
+        // which would panic. Not part of the official 56 mnemonics, so it's
+        // excluded from `Mnemonic`/`mnemonic_enum` — see
+        // `test_every_table_entry_has_a_recognized_mnemonic_enum`.
+        OpCode::new(0x02, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0x12, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0x22, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0x32, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0x42, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0x52, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0x62, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0x72, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0x92, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0xb2, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0xd2, "KIL", 1, 2, NoneAddressing),
+        OpCode::new(0xf2, "KIL", 1, 2, NoneAddressing),
+    ]
+};
+
+/// Direct `opcode byte -> OpCode` lookup table, indexed by the raw byte: a
+/// plain array index is faster than a `HashMap` get on the CPU's hot path
+/// (every single instruction fetch goes through this). Built once at
+/// compile time from [`CPU_OPS_CODES`] rather than lazily at first use,
+/// since the source data is itself a `const`.
+pub static OPCODES_TABLE: [Option<&'static OpCode>; 256] = build_opcodes_table();
+
+const fn build_opcodes_table() -> [Option<&'static OpCode>; 256] {
+    let mut table: [Option<&'static OpCode>; 256] = [None; 256];
+    let mut i = 0;
+    while i < CPU_OPS_CODES.len() {
+        let op = &CPU_OPS_CODES[i];
+        table[op.code as usize] = Some(op);
+        i += 1;
+    }
+    table
+}
+
+/// Scans [`CPU_OPS_CODES`] (rather than [`OPCODES_TABLE`]) for `code`'s
+/// entry. Shared by [`opcode_len`]/[`opcode_cycles`] so they can run in
+/// `const` contexts: a `const fn` can't index into a `static` like
+/// `OPCODES_TABLE`, only a `const` like `CPU_OPS_CODES`, which is a short
+/// enough list that a linear scan costs nothing a build step would avoid.
+const fn find_opcode(code: u8) -> Option<&'static OpCode> {
+    let mut i = 0;
+    while i < CPU_OPS_CODES.len() {
+        if CPU_OPS_CODES[i].code == code {
+            return Some(&CPU_OPS_CODES[i]);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// `code`'s instruction length in bytes (opcode included), usable at
+/// compile time — e.g. for compile-time buffer sizing — unlike a lookup
+/// through [`OPCODES_TABLE`]. `0` for a byte with no assigned instruction.
+pub const fn opcode_len(code: u8) -> u8 {
+    match find_opcode(code) {
+        Some(op) => op.len,
+        None => 0,
+    }
+}
+
+/// `code`'s base cycle count (before any page-cross penalty), usable at
+/// compile time the same way [`opcode_len`] is. `0` for a byte with no
+/// assigned instruction.
+pub const fn opcode_cycles(code: u8) -> u8 {
+    match find_opcode(code) {
+        Some(op) => op.cycles,
+        None => 0,
+    }
+}
+
+/// Looks up the `OpCode` for a `(mnemonic, mode)` pair, e.g. for an
+/// assembler encoding instructions back into bytes.
+///
+/// Mnemonics that support several addressing modes (like `ASL`, which has
+/// both an `Accumulator` and a `ZeroPage` form) are disambiguated by mode.
+pub fn opcode_for(mnemonic: &str, mode: AddressingMode) -> Option<&'static OpCode> {
+    CPU_OPS_CODES
+        .iter()
+        .find(|op| op.mnemonic == mnemonic && op.mode == mode)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_opcode_for_lda_immediate() {
+        let op = opcode_for("LDA", AddressingMode::Immediate).unwrap();
+        assert_eq!(op.code, 0xa9);
+    }
+
+    #[test]
+    fn test_opcode_for_distinguishes_accumulator_from_zero_page() {
+        let acc = opcode_for("ASL", AddressingMode::Accumulator).unwrap();
+        assert_eq!(acc.code, 0x0a);
+
+        let zp = opcode_for("ASL", AddressingMode::ZeroPage).unwrap();
+        assert_eq!(zp.code, 0x06);
+    }
+
+    #[test]
+    fn test_opcode_for_unknown_combination_returns_none() {
+        assert!(opcode_for("LDA", AddressingMode::Accumulator).is_none());
+    }
+
+    #[test]
+    fn test_opcode_len_evaluates_in_a_const_context() {
+        const LDA_IMMEDIATE_LEN: u8 = opcode_len(0xa9);
+        assert_eq!(LDA_IMMEDIATE_LEN, 2);
+    }
+
+    #[test]
+    fn test_opcode_len_and_cycles_match_the_table_for_every_assigned_opcode() {
+        for opcode in CPU_OPS_CODES.iter() {
+            assert_eq!(opcode_len(opcode.code), opcode.len);
+            assert_eq!(opcode_cycles(opcode.code), opcode.cycles);
+        }
+    }
+
+    #[test]
+    fn test_opcode_len_and_cycles_are_zero_for_an_unassigned_byte() {
+        assert!(OPCODES_TABLE[0x03].is_none());
+        assert_eq!(opcode_len(0x03), 0);
+        assert_eq!(opcode_cycles(0x03), 0);
+    }
+
+    #[test]
+    fn test_try_from_u8_maps_0xa9_to_lda() {
+        assert_eq!(Mnemonic::try_from(0xa9).unwrap(), Mnemonic::LDA);
+    }
+
+    #[test]
+    fn test_try_from_u8_rejects_an_unassigned_opcode_byte() {
+        assert_eq!(Mnemonic::try_from(0x03), Err(UnrecognizedOpcode(0x03)));
+    }
+
+    #[test]
+    fn test_try_from_u8_rejects_an_undocumented_kil_opcode() {
+        // KIL is in the table (see `CPU::step`'s "KIL" arm) but isn't one
+        // of the 56 official mnemonics, so it has no `Mnemonic` variant.
+        assert_eq!(Mnemonic::try_from(0x02), Err(UnrecognizedOpcode(0x02)));
+    }
+
+    #[test]
+    fn test_every_table_entry_has_a_recognized_mnemonic_enum() {
+        for opcode in CPU_OPS_CODES.iter() {
+            if opcode.mnemonic == "KIL" {
+                assert_eq!(opcode.mnemonic_enum_checked(), None);
+                continue;
+            }
+            let mnemonic = opcode.mnemonic_enum();
+            assert_eq!(Mnemonic::try_from(opcode.code).unwrap(), mnemonic);
+        }
+    }
+
+    #[test]
+    fn test_operand_bytes_for_every_mode() {
+        use AddressingMode::*;
+        assert_eq!(operand_bytes(&NoneAddressing), 0);
+        assert_eq!(operand_bytes(&Accumulator), 0);
+        assert_eq!(operand_bytes(&Immediate), 1);
+        assert_eq!(operand_bytes(&ZeroPage), 1);
+        assert_eq!(operand_bytes(&ZeroPage_X), 1);
+        assert_eq!(operand_bytes(&ZeroPage_Y), 1);
+        assert_eq!(operand_bytes(&Indirect_X), 1);
+        assert_eq!(operand_bytes(&Indirect_Y), 1);
+        assert_eq!(operand_bytes(&Absolute), 2);
+        assert_eq!(operand_bytes(&Absolute_X), 2);
+        assert_eq!(operand_bytes(&Absolute_Y), 2);
+    }
+
+    #[test]
+    fn test_is_branch_recognizes_all_eight_relative_branches_and_nothing_else() {
+        for mnemonic in ["BCC", "BCS", "BEQ", "BNE", "BMI", "BPL", "BVC", "BVS"] {
+            assert!(is_branch(mnemonic), "{mnemonic} should be a branch");
+        }
+        for mnemonic in ["JMP", "JSR", "NOP", "BRK"] {
+            assert!(!is_branch(mnemonic), "{mnemonic} should not be a branch");
+        }
+    }
+
+    #[test]
+    fn test_operand_bytes_matches_declared_length_for_every_addressed_mode() {
+        // `NoneAddressing` also covers implied opcodes (0 operand bytes,
+        // consistent with `operand_bytes`) and the relative branches plus
+        // `JMP`/`JSR` (1 or 2 operand bytes, NOT modeled by the addressing
+        // mode — see `operand_bytes`'s doc comment), so it's excluded here
+        // rather than asserted against.
+        for opcode in CPU_OPS_CODES.iter() {
+            if opcode.mode == AddressingMode::NoneAddressing {
+                continue;
+            }
+            assert_eq!(
+                opcode.len,
+                1 + operand_bytes(&opcode.mode),
+                "{} ({:#04x})",
+                opcode.mnemonic,
+                opcode.code
+            );
+        }
+    }
+
+    #[test]
+    fn test_page_cross_penalty_matches_the_documented_set() {
+        // Indexed reads pay the conditional +1 on a page cross.
+        assert!(
+            opcode_for("LDA", AddressingMode::Absolute_X)
+                .unwrap()
+                .page_cross_penalty
+        );
+        assert!(
+            opcode_for("ADC", AddressingMode::Absolute_Y)
+                .unwrap()
+                .page_cross_penalty
+        );
+        assert!(
+            opcode_for("CMP", AddressingMode::Indirect_Y)
+                .unwrap()
+                .page_cross_penalty
+        );
+
+        // Stores always take their fixed cycle count, page cross or not.
+        assert!(
+            !opcode_for("STA", AddressingMode::Absolute_X)
+                .unwrap()
+                .page_cross_penalty
+        );
+
+        // Read-modify-write instructions already charge their maximum cycle
+        // count in the table, so they never get the extra penalty either.
+        assert!(
+            !opcode_for("ASL", AddressingMode::Absolute_X)
+                .unwrap()
+                .page_cross_penalty
+        );
+        assert!(
+            !opcode_for("INC", AddressingMode::Absolute_X)
+                .unwrap()
+                .page_cross_penalty
+        );
+    }
+}
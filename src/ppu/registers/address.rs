@@ -0,0 +1,74 @@
+/// The PPU's `$2006` address port.
+///
+/// Two writes build up a 16-bit VRAM address one byte at a time (high byte
+/// first), and reads auto-increment it; see [`super::control::ControlRegister`]
+/// for the increment step.
+///
+/// Unlike the real PPU, this register no longer owns the write toggle that
+/// picks which half a write lands on — PPUADDR (`$2006`) shares that toggle
+/// with PPUSCROLL (`$2005`), so [`super::super::NesPpu`] owns it and calls
+/// [`Self::update_hi`]/[`Self::update_lo`] directly.
+#[derive(Clone, Copy)]
+pub struct AddrRegister {
+    value: (u8, u8), // (hi, lo)
+}
+
+impl AddrRegister {
+    pub fn new() -> Self {
+        AddrRegister { value: (0, 0) }
+    }
+
+    fn set(&mut self, data: u16) {
+        self.value.0 = (data >> 8) as u8;
+        self.value.1 = (data & 0xff) as u8;
+    }
+
+    /// Writes the high byte (the first write in the two-write `$2006`
+    /// protocol).
+    pub fn update_hi(&mut self, data: u8) {
+        self.value.0 = data;
+        if self.get() > 0x3fff {
+            self.set(self.get() & 0b0011_1111_1111_1111);
+        }
+    }
+
+    /// Writes the low byte (the second write in the two-write `$2006`
+    /// protocol).
+    pub fn update_lo(&mut self, data: u8) {
+        self.value.1 = data;
+        if self.get() > 0x3fff {
+            self.set(self.get() & 0b0011_1111_1111_1111);
+        }
+    }
+
+    pub fn increment(&mut self, inc: u8) {
+        let lo = self.value.1;
+        self.value.1 = self.value.1.wrapping_add(inc);
+        if lo > self.value.1 {
+            self.value.0 = self.value.0.wrapping_add(1);
+        }
+        if self.get() > 0x3fff {
+            self.set(self.get() & 0b0011_1111_1111_1111);
+        }
+    }
+
+    /// Directly overwrites the address with `value`, masked down to 14 bits
+    /// the same way [`Self::update_hi`]/[`Self::update_lo`]/[`Self::increment`]
+    /// do. Used by the
+    /// rendering-time coarse increment path, which computes a new value
+    /// outside the normal two-write `$2006` protocol and writes it straight
+    /// back.
+    pub fn set_raw(&mut self, value: u16) {
+        self.set(value & 0b0011_1111_1111_1111);
+    }
+
+    pub fn get(&self) -> u16 {
+        ((self.value.0 as u16) << 8) | (self.value.1 as u16)
+    }
+}
+
+impl Default for AddrRegister {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,65 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// The PPU's `$2000` control register.
+    ///
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// VPHB SINN
+    /// |||| ||||
+    /// |||| ||++- Base nametable address
+    /// |||| |+--- VRAM address increment per CPU read/write of PPUDATA
+    /// |||| +---- Sprite pattern table address for 8x8 sprites
+    /// |||+------ Background pattern table address
+    /// ||+------- Sprite size
+    /// |+-------- PPU master/slave select
+    /// +--------- Generate an NMI at the start of vertical blanking
+    /// ```
+    #[derive(Clone, Copy)]
+    pub struct ControlRegister: u8 {
+        const NAMETABLE1              = 0b0000_0001;
+        const NAMETABLE2              = 0b0000_0010;
+        const VRAM_ADD_INCREMENT      = 0b0000_0100;
+        const SPRITE_PATTERN_ADDR     = 0b0000_1000;
+        const BACKGROUND_PATTERN_ADDR = 0b0001_0000;
+        const SPRITE_SIZE             = 0b0010_0000;
+        const MASTER_SLAVE_SELECT     = 0b0100_0000;
+        const GENERATE_NMI            = 0b1000_0000;
+    }
+}
+
+impl ControlRegister {
+    pub fn new() -> Self {
+        ControlRegister::from_bits_truncate(0b0000_0000)
+    }
+
+    pub fn vram_addr_increment(&self) -> u8 {
+        if self.contains(ControlRegister::VRAM_ADD_INCREMENT) {
+            32
+        } else {
+            1
+        }
+    }
+
+    /// CHR-ROM/RAM offset of the background tile pattern table: `$1000` if
+    /// the background is configured to use the second pattern table,
+    /// `$0000` otherwise.
+    pub fn background_pattern_addr(&self) -> usize {
+        if self.contains(ControlRegister::BACKGROUND_PATTERN_ADDR) {
+            0x1000
+        } else {
+            0
+        }
+    }
+
+    pub fn update(&mut self, data: u8) {
+        *self = ControlRegister::from_bits_truncate(data);
+    }
+}
+
+impl Default for ControlRegister {
+    fn default() -> Self {
+        Self::new()
+    }
+}
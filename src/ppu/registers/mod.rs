@@ -0,0 +1,3 @@
+pub mod address;
+pub mod control;
+pub mod status;
@@ -0,0 +1,28 @@
+use bitflags::bitflags;
+
+bitflags! {
+    /// The PPU's `$2002` status register.
+    ///
+    /// ```text
+    /// 7  bit  0
+    /// ---- ----
+    /// VSO. ....
+    /// |||| ||||
+    /// |||+-++++- Stale PPU bus contents (not modeled)
+    /// ||+------- Sprite overflow: more than 8 sprites on a scanline
+    /// |+-------- Sprite 0 hit
+    /// +--------- Vertical blank has started
+    /// ```
+    #[derive(Default, Clone, Copy)]
+    pub struct StatusRegister: u8 {
+        const SPRITE_OVERFLOW = 0b0010_0000;
+        const SPRITE_ZERO_HIT = 0b0100_0000;
+        const VBLANK_STARTED  = 0b1000_0000;
+    }
+}
+
+impl StatusRegister {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
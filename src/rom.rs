@@ -0,0 +1,320 @@
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::ppu::Mirroring;
+
+const NES_TAG: [u8; 4] = [0x4e, 0x45, 0x53, 0x1a];
+const PRG_ROM_PAGE_SIZE: usize = 16384;
+const CHR_ROM_PAGE_SIZE: usize = 8192;
+
+/// Why [`Rom::new`] (or the `TryFrom` impls built on it) rejected a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RomError {
+    /// The file doesn't start with the `NES\x1a` magic number.
+    BadMagic,
+    /// The header declares NES 2.0, which isn't supported yet — including
+    /// its byte-8 submapper field and extended PRG/CHR size encoding.
+    UnsupportedVersion,
+    /// The file is shorter than its header's PRG/CHR bank counts promise.
+    Truncated,
+    /// The mapper number isn't one this emulator can run (only NROM/mapper
+    /// 0 is implemented; bank-switching mappers land in later revisions).
+    UnsupportedMapper(u8),
+    /// [`Rom::from_path`] or [`Rom::from_reader`] couldn't read the
+    /// underlying file/stream at all, short-circuiting before the bytes
+    /// that *were* read ever reach [`Rom::new`]. Carries the `io::Error`'s
+    /// message rather than the error itself, since `io::Error` isn't
+    /// `PartialEq`/`Eq` and the other variants need to stay comparable.
+    Io(String),
+}
+
+impl fmt::Display for RomError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RomError::BadMagic => write!(f, "file is not in iNES file format"),
+            RomError::UnsupportedVersion => write!(f, "NES2.0 format is not supported"),
+            RomError::Truncated => write!(f, "file is truncated relative to its header"),
+            RomError::UnsupportedMapper(mapper) => write!(f, "mapper {mapper} is not supported"),
+            RomError::Io(message) => write!(f, "failed to read ROM: {message}"),
+        }
+    }
+}
+
+impl std::error::Error for RomError {}
+
+/// A parsed iNES cartridge image: PRG/CHR ROM banks plus the header fields
+/// the bus and mapper need to wire things up.
+#[derive(Debug)]
+pub struct Rom {
+    pub prg_rom: Vec<u8>,
+    pub chr_rom: Vec<u8>,
+    /// The full 8-bit mapper number: flag-7's high nibble combined with
+    /// flag-6's high nibble, since plenty of real mappers (e.g. 66, GxROM)
+    /// only show up correctly once both halves are read. A submapper
+    /// (iNES 2.0 byte 8) would refine this further, but NES 2.0 headers are
+    /// rejected by [`RomError::UnsupportedVersion`] before we'd ever get
+    /// there — see that variant's doc comment.
+    pub mapper: u8,
+    pub mirroring: Mirroring,
+    /// Set when the header reports zero CHR-ROM banks, meaning the
+    /// cartridge provides 8KB of writable CHR-RAM instead. `chr_rom` is
+    /// still where that RAM lives; this just tells the PPU it may write
+    /// through to it.
+    pub chr_ram: bool,
+}
+
+/// Real hardware puts 8KB of CHR-RAM on CHR-RAM carts.
+const CHR_RAM_SIZE: usize = 8192;
+
+impl Rom {
+    /// Parses a raw iNES file. Only mapper-agnostic header fields are
+    /// validated here, plus a check that the mapper itself is one this
+    /// emulator can run; NES 2.0 and non-NROM bank switching land in later
+    /// revisions.
+    pub fn new(raw: &[u8]) -> Result<Rom, RomError> {
+        if raw.len() < 16 || raw[0..4] != NES_TAG {
+            return Err(RomError::BadMagic);
+        }
+
+        let ines_ver = (raw[7] >> 2) & 0b11;
+        if ines_ver != 0 {
+            return Err(RomError::UnsupportedVersion);
+        }
+
+        let mapper = (raw[7] & 0b1111_0000) | (raw[6] >> 4);
+        if mapper != 0 {
+            return Err(RomError::UnsupportedMapper(mapper));
+        }
+
+        let four_screen = raw[6] & 0b1000 != 0;
+        let vertical_mirroring = raw[6] & 0b1 != 0;
+        let mirroring = match (four_screen, vertical_mirroring) {
+            (true, _) => Mirroring::FourScreen,
+            (false, true) => Mirroring::Vertical,
+            (false, false) => Mirroring::Horizontal,
+        };
+
+        let prg_rom_size = raw[4] as usize * PRG_ROM_PAGE_SIZE;
+        let chr_rom_size = raw[5] as usize * CHR_ROM_PAGE_SIZE;
+
+        let skip_trainer = raw[6] & 0b100 != 0;
+
+        let prg_rom_start = 16 + if skip_trainer { 512 } else { 0 };
+        let chr_rom_start = prg_rom_start + prg_rom_size;
+
+        if raw.len() < chr_rom_start + chr_rom_size {
+            return Err(RomError::Truncated);
+        }
+
+        let chr_ram = chr_rom_size == 0;
+        let chr_rom = if chr_ram {
+            vec![0; CHR_RAM_SIZE]
+        } else {
+            raw[chr_rom_start..(chr_rom_start + chr_rom_size)].to_vec()
+        };
+
+        Ok(Rom {
+            prg_rom: raw[prg_rom_start..(prg_rom_start + prg_rom_size)].to_vec(),
+            chr_rom,
+            mapper,
+            mirroring,
+            chr_ram,
+        })
+    }
+
+    /// Reads and parses an iNES file from `path`, so callers don't have to
+    /// hand-read the file themselves before calling [`Self::new`].
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Rom, RomError> {
+        let raw = fs::read(path).map_err(|err| RomError::Io(err.to_string()))?;
+        Rom::new(&raw)
+    }
+
+    /// Reads `reader` to the end, then parses the result as an iNES file.
+    /// Like [`Self::from_path`], but for a ROM already in memory, over the
+    /// network, or anywhere else behind a [`Read`] impl rather than a path
+    /// on disk.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Rom, RomError> {
+        let mut raw = Vec::new();
+        reader
+            .read_to_end(&mut raw)
+            .map_err(|err| RomError::Io(err.to_string()))?;
+        Rom::new(&raw)
+    }
+}
+
+impl TryFrom<&[u8]> for Rom {
+    type Error = RomError;
+
+    fn try_from(raw: &[u8]) -> Result<Rom, RomError> {
+        Rom::new(raw)
+    }
+}
+
+impl TryFrom<Vec<u8>> for Rom {
+    type Error = RomError;
+
+    fn try_from(raw: Vec<u8>) -> Result<Rom, RomError> {
+        Rom::new(&raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn build_ines(prg_banks: u8, chr_banks: u8, flag6: u8, flag7: u8) -> Vec<u8> {
+        let mut raw = vec![0x4e, 0x45, 0x53, 0x1a, prg_banks, chr_banks, flag6, flag7];
+        raw.extend(std::iter::repeat_n(0, 8)); // rest of the 16-byte header
+        raw.extend(std::iter::repeat_n(
+            1,
+            prg_banks as usize * PRG_ROM_PAGE_SIZE,
+        ));
+        raw.extend(std::iter::repeat_n(
+            2,
+            chr_banks as usize * CHR_ROM_PAGE_SIZE,
+        ));
+        raw
+    }
+
+    #[test]
+    fn test_parses_prg_and_chr_banks() {
+        let raw = build_ines(2, 1, 0, 0);
+        let rom = Rom::new(&raw).unwrap();
+        assert_eq!(rom.prg_rom.len(), 2 * PRG_ROM_PAGE_SIZE);
+        assert_eq!(rom.chr_rom.len(), CHR_ROM_PAGE_SIZE);
+        assert!(rom.prg_rom.iter().all(|&b| b == 1));
+        assert!(rom.chr_rom.iter().all(|&b| b == 2));
+    }
+
+    #[test]
+    fn test_rejects_missing_magic_number() {
+        let raw = vec![0; 32];
+        assert!(Rom::new(&raw).is_err());
+    }
+
+    #[test]
+    fn test_bad_magic_error_variant() {
+        let raw = vec![0; 32];
+        assert_eq!(Rom::new(&raw).unwrap_err(), RomError::BadMagic);
+    }
+
+    #[test]
+    fn test_unsupported_version_error_variant() {
+        let mut raw = build_ines(1, 1, 0, 0);
+        raw[7] |= 0b0000_1000; // ines_ver = 2 (NES 2.0)
+        assert_eq!(Rom::new(&raw).unwrap_err(), RomError::UnsupportedVersion);
+    }
+
+    #[test]
+    fn test_truncated_error_variant() {
+        let mut raw = build_ines(2, 1, 0, 0);
+        raw.truncate(raw.len() - 1);
+        assert_eq!(Rom::new(&raw).unwrap_err(), RomError::Truncated);
+    }
+
+    #[test]
+    fn test_unsupported_mapper_error_variant() {
+        // Mapper number 1 (MMC1) split across flag6's high nibble and
+        // flag7's high nibble.
+        let raw = build_ines(1, 1, 0b0001_0000, 0);
+        assert_eq!(Rom::new(&raw).unwrap_err(), RomError::UnsupportedMapper(1));
+    }
+
+    #[test]
+    fn test_mapper_66_combines_both_nibbles() {
+        // Mapper 66 (GxROM) needs both halves: flag6's high nibble (0x2)
+        // and flag7's high nibble (0x4) combine to 0x42 = 66. Reading only
+        // flag6's nibble (as some loaders do) would misidentify this as
+        // mapper 2.
+        let raw = build_ines(1, 1, 0b0010_0000, 0b0100_0000);
+        assert_eq!(Rom::new(&raw).unwrap_err(), RomError::UnsupportedMapper(66));
+    }
+
+    #[test]
+    fn test_try_from_slice_succeeds_via_try_into() {
+        let raw = build_ines(1, 1, 0, 0);
+        let rom: Rom = raw.as_slice().try_into().unwrap();
+        assert_eq!(rom.prg_rom.len(), PRG_ROM_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_try_from_vec_succeeds_via_try_into() {
+        let raw = build_ines(1, 1, 0, 0);
+        let rom: Rom = raw.try_into().unwrap();
+        assert_eq!(rom.prg_rom.len(), PRG_ROM_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_mirroring_from_flag6() {
+        let vertical = build_ines(1, 1, 0b0000_0001, 0);
+        assert_eq!(Rom::new(&vertical).unwrap().mirroring, Mirroring::Vertical);
+
+        let horizontal = build_ines(1, 1, 0b0000_0000, 0);
+        assert_eq!(
+            Rom::new(&horizontal).unwrap().mirroring,
+            Mirroring::Horizontal
+        );
+    }
+
+    #[test]
+    fn test_zero_chr_banks_allocates_8kb_of_chr_ram() {
+        let raw = build_ines(1, 0, 0, 0);
+        let rom = Rom::new(&raw).unwrap();
+        assert!(rom.chr_ram);
+        assert_eq!(rom.chr_rom.len(), CHR_RAM_SIZE);
+        assert!(rom.chr_rom.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_nonzero_chr_banks_is_not_chr_ram() {
+        let raw = build_ines(1, 1, 0, 0);
+        let rom = Rom::new(&raw).unwrap();
+        assert!(!rom.chr_ram);
+    }
+
+    #[test]
+    fn test_from_path_reads_and_parses_a_temp_file() {
+        let raw = build_ines(1, 1, 0, 0);
+        let mut path = std::env::temp_dir();
+        path.push("nes_emulator_synth178_from_path_test.nes");
+        std::fs::write(&path, &raw).unwrap();
+
+        let rom = Rom::from_path(&path).unwrap();
+        assert_eq!(rom.prg_rom.len(), PRG_ROM_PAGE_SIZE);
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_from_path_reports_io_error_for_a_missing_file() {
+        let err = Rom::from_path("/nonexistent/nes_emulator_synth178.nes").unwrap_err();
+        assert!(matches!(err, RomError::Io(_)));
+    }
+
+    #[test]
+    fn test_from_reader_reads_a_cursor_to_the_end_then_parses() {
+        let raw = build_ines(1, 1, 0, 0);
+        let cursor = std::io::Cursor::new(raw);
+
+        let rom = Rom::from_reader(cursor).unwrap();
+        assert_eq!(rom.prg_rom.len(), PRG_ROM_PAGE_SIZE);
+    }
+
+    #[test]
+    fn test_four_screen_flag_overrides_the_vertical_horizontal_bit() {
+        // Bit 3 set wins regardless of bit 0, per the iNES spec.
+        let four_screen_vertical = build_ines(1, 1, 0b0000_1001, 0);
+        assert_eq!(
+            Rom::new(&four_screen_vertical).unwrap().mirroring,
+            Mirroring::FourScreen
+        );
+
+        let four_screen_horizontal = build_ines(1, 1, 0b0000_1000, 0);
+        assert_eq!(
+            Rom::new(&four_screen_horizontal).unwrap().mirroring,
+            Mirroring::FourScreen
+        );
+    }
+}
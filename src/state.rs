@@ -0,0 +1,294 @@
+//! Save states: a snapshot of a running [`crate::nes::Nes`] that can be
+//! restored later via [`crate::nes::Nes::load_state`].
+//!
+//! This only captures hardware this emulator actually models: the CPU, the
+//! 2KB internal RAM, and the PPU's VRAM/OAM/palette/registers (including
+//! CHR-RAM, since UxROM-style carts can write through to it), plus the
+//! first controller's strobe/button state and the frame counter. There's no
+//! APU channel state to capture — [`crate::apu`] is length-counter/mute
+//! building blocks not yet wired into [`crate::bus::Bus`] — no mapper
+//! register state, since the only mapper implemented (NROM) has none, and
+//! no battery RAM, since nothing in this tree models cartridge SRAM. Each
+//! of those lands here once the corresponding hardware does.
+//!
+//! The framebuffer itself isn't stored: [`crate::ppu::NesPpu::render`] is a
+//! pure function of the restored PPU state, so [`crate::nes::Nes::load_state`]
+//! just re-renders it.
+//!
+//! The format is a flat, versioned byte encoding (length-prefixed where a
+//! field isn't fixed-size) rather than a third-party serialization crate,
+//! keeping the crate's dependencies to `bitflags` alone (see
+//! `Cargo.toml`).
+
+use std::fmt;
+
+use crate::cpu::Region;
+
+const MAGIC: [u8; 4] = *b"NSST";
+const VERSION: u8 = 2;
+
+/// Why [`SystemState::from_bytes`] rejected a save state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateError {
+    /// The data doesn't start with the save-state magic number.
+    BadMagic,
+    /// The data declares a format version this build doesn't know how to
+    /// read.
+    UnsupportedVersion(u8),
+    /// The data ends partway through a field.
+    Truncated,
+    /// The CHR data's length doesn't match the currently loaded cartridge's,
+    /// meaning this save state was made against a different ROM.
+    ChrSizeMismatch,
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::BadMagic => write!(f, "not a save state file"),
+            StateError::UnsupportedVersion(v) => write!(f, "unsupported save state version {v}"),
+            StateError::Truncated => write!(f, "save state data is truncated"),
+            StateError::ChrSizeMismatch => {
+                write!(f, "save state was made against a different ROM")
+            }
+        }
+    }
+}
+
+impl std::error::Error for StateError {}
+
+/// Everything [`SystemState`] captures about the CPU. Built and consumed by
+/// [`crate::nes::Nes::save_state`]/[`crate::nes::Nes::load_state`], which
+/// have the private field access to fill and apply it.
+pub(crate) struct CpuState {
+    pub register_a: u8,
+    pub register_x: u8,
+    pub register_y: u8,
+    pub status: u8,
+    pub program_counter: u16,
+    pub stack_pointer: u8,
+    pub cycles: u64,
+    pub region: Region,
+    pub interrupts: (bool, bool, bool, u8),
+}
+
+/// Everything [`SystemState`] captures about the PPU.
+pub(crate) struct PpuState {
+    pub chr_rom: Vec<u8>,
+    pub vram: Vec<u8>,
+    pub oam_data: [u8; 256],
+    pub palette_table: [u8; 32],
+    pub ctrl_bits: u8,
+    pub status_bits: u8,
+    pub addr: u16,
+    pub internal_data_buf: u8,
+    pub vblank: bool,
+    pub nmi_line: bool,
+    pub rendering_active: bool,
+    pub io_latch: u8,
+    pub scroll_x: u8,
+    pub scroll_y: u8,
+    pub write_toggle: bool,
+    pub scanline: u16,
+    pub dot: u16,
+    pub scanlines_per_frame: u16,
+}
+
+/// A versioned, point-in-time snapshot of a [`crate::nes::Nes`], suitable
+/// for writing to disk and restoring later. Built by
+/// [`crate::nes::Nes::save_state`]; restored by
+/// [`crate::nes::Nes::load_state`]. Constructing or reading one outside
+/// those two methods isn't expected — there's no public constructor.
+pub struct SystemState {
+    pub(crate) cpu: CpuState,
+    pub(crate) ram: [u8; 2048],
+    pub(crate) ppu: PpuState,
+    pub(crate) joypad1: (bool, u8, u8),
+    pub(crate) frame_count: u64,
+}
+
+impl SystemState {
+    pub(crate) fn new(
+        cpu: CpuState,
+        ram: [u8; 2048],
+        ppu: PpuState,
+        joypad1: (bool, u8, u8),
+        frame_count: u64,
+    ) -> Self {
+        SystemState {
+            cpu,
+            ram,
+            ppu,
+            joypad1,
+            frame_count,
+        }
+    }
+
+    /// Encodes this state as a flat, versioned byte buffer.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        out.extend_from_slice(&MAGIC);
+        out.push(VERSION);
+
+        out.push(self.cpu.register_a);
+        out.push(self.cpu.register_x);
+        out.push(self.cpu.register_y);
+        out.push(self.cpu.status);
+        out.extend_from_slice(&self.cpu.program_counter.to_le_bytes());
+        out.push(self.cpu.stack_pointer);
+        out.extend_from_slice(&self.cpu.cycles.to_le_bytes());
+        out.push(self.cpu.region as u8);
+        let (nmi_pending, irq_pending, page_crossed, clock_cycles_remaining) = self.cpu.interrupts;
+        out.push(nmi_pending as u8);
+        out.push(irq_pending as u8);
+        out.push(page_crossed as u8);
+        out.push(clock_cycles_remaining);
+
+        out.extend_from_slice(&self.ram);
+
+        write_bytes(&mut out, &self.ppu.chr_rom);
+        write_bytes(&mut out, &self.ppu.vram);
+        out.extend_from_slice(&self.ppu.oam_data);
+        out.extend_from_slice(&self.ppu.palette_table);
+        out.push(self.ppu.ctrl_bits);
+        out.push(self.ppu.status_bits);
+        out.extend_from_slice(&self.ppu.addr.to_le_bytes());
+        out.push(self.ppu.internal_data_buf);
+        out.push(self.ppu.vblank as u8);
+        out.push(self.ppu.nmi_line as u8);
+        out.push(self.ppu.rendering_active as u8);
+        out.push(self.ppu.io_latch);
+        out.push(self.ppu.scroll_x);
+        out.push(self.ppu.scroll_y);
+        out.push(self.ppu.write_toggle as u8);
+        out.extend_from_slice(&self.ppu.scanline.to_le_bytes());
+        out.extend_from_slice(&self.ppu.dot.to_le_bytes());
+        out.extend_from_slice(&self.ppu.scanlines_per_frame.to_le_bytes());
+
+        let (strobe, button_index, status_bits) = self.joypad1;
+        out.push(strobe as u8);
+        out.push(button_index);
+        out.push(status_bits);
+
+        out.extend_from_slice(&self.frame_count.to_le_bytes());
+
+        out
+    }
+
+    /// Decodes a buffer produced by [`Self::to_bytes`].
+    pub fn from_bytes(data: &[u8]) -> Result<Self, StateError> {
+        let mut r = Reader::new(data);
+        if r.take(4)? != MAGIC {
+            return Err(StateError::BadMagic);
+        }
+        let version = r.u8()?;
+        if version != VERSION {
+            return Err(StateError::UnsupportedVersion(version));
+        }
+
+        let cpu = CpuState {
+            register_a: r.u8()?,
+            register_x: r.u8()?,
+            register_y: r.u8()?,
+            status: r.u8()?,
+            program_counter: r.u16()?,
+            stack_pointer: r.u8()?,
+            cycles: r.u64()?,
+            region: match r.u8()? {
+                1 => Region::Pal,
+                _ => Region::Ntsc,
+            },
+            interrupts: (r.u8()? != 0, r.u8()? != 0, r.u8()? != 0, r.u8()?),
+        };
+
+        let mut ram = [0u8; 2048];
+        ram.copy_from_slice(r.take(2048)?);
+
+        let chr_rom = r.bytes()?.to_vec();
+        let vram = r.bytes()?.to_vec();
+        let mut oam_data = [0u8; 256];
+        oam_data.copy_from_slice(r.take(256)?);
+        let mut palette_table = [0u8; 32];
+        palette_table.copy_from_slice(r.take(32)?);
+        let ppu = PpuState {
+            chr_rom,
+            vram,
+            oam_data,
+            palette_table,
+            ctrl_bits: r.u8()?,
+            status_bits: r.u8()?,
+            addr: r.u16()?,
+            internal_data_buf: r.u8()?,
+            vblank: r.u8()? != 0,
+            nmi_line: r.u8()? != 0,
+            rendering_active: r.u8()? != 0,
+            io_latch: r.u8()?,
+            scroll_x: r.u8()?,
+            scroll_y: r.u8()?,
+            write_toggle: r.u8()? != 0,
+            scanline: r.u16()?,
+            dot: r.u16()?,
+            scanlines_per_frame: r.u16()?,
+        };
+
+        let joypad1 = (r.u8()? != 0, r.u8()?, r.u8()?);
+        let frame_count = r.u64()?;
+
+        Ok(SystemState {
+            cpu,
+            ram,
+            ppu,
+            joypad1,
+            frame_count,
+        })
+    }
+}
+
+/// Appends `bytes` length-prefixed (`u32` little-endian length, then the
+/// bytes), for the PPU's variably-sized CHR/VRAM buffers (CHR-RAM carts and
+/// four-screen mirroring both change their size from the common case).
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+/// A cursor over a save-state byte buffer, failing with
+/// [`StateError::Truncated`] instead of panicking on a short read.
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Reader { data, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], StateError> {
+        let end = self.pos.checked_add(len).ok_or(StateError::Truncated)?;
+        let slice = self.data.get(self.pos..end).ok_or(StateError::Truncated)?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u8(&mut self) -> Result<u8, StateError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, StateError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, StateError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8], StateError> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    fn u32(&mut self) -> Result<u32, StateError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+}
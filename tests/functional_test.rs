@@ -0,0 +1,37 @@
+//! Runs Klaus Dormann's 6502 functional test suite against the `CPU`, the
+//! closest thing the 6502 community has to a gold-standard correctness
+//! fixture: it exercises nearly every opcode/flag/addressing-mode
+//! combination and traps (jumps to itself) at a well-known address on
+//! success, or anywhere else on failure.
+//!
+//! The binary itself (`6502_functional_test.bin`, public domain) isn't
+//! checked into `tests/fixtures/` in this environment since it couldn't be
+//! fetched here. The runner below is wired up and ready to go the moment it
+//! is dropped in, so this is marked `#[ignore]` rather than deleted.
+
+use nes_emulator::cpu::CPU;
+use nes_emulator::mem::{FlatMemory, Mem};
+
+const LOAD_ADDR: u16 = 0x0400;
+const SUCCESS_TRAP: u16 = 0x3469;
+
+#[test]
+#[ignore = "requires tests/fixtures/6502_functional_test.bin, not present in this checkout"]
+fn test_6502_functional_test_rom_reaches_the_success_trap() {
+    let program = std::fs::read("tests/fixtures/6502_functional_test.bin")
+        .expect("6502_functional_test.bin should be present in tests/fixtures");
+
+    let mut mem = FlatMemory::new();
+    for (i, byte) in program.iter().enumerate() {
+        mem.mem_write(LOAD_ADDR + i as u16, *byte);
+    }
+
+    let mut cpu = CPU::new(mem);
+    cpu.program_counter = LOAD_ADDR;
+
+    let trap_pc = cpu.run_until_trap();
+    assert_eq!(
+        trap_pc, SUCCESS_TRAP,
+        "trapped at {trap_pc:#06x} instead of the documented success address {SUCCESS_TRAP:#06x}"
+    );
+}